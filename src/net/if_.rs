@@ -5,6 +5,7 @@
 
 use crate::{Error, NixPath, Result};
 use libc::c_uint;
+use std::ffi::{CStr, CString};
 
 /// Resolve an interface into a interface number.
 pub fn if_nametoindex<P: ?Sized + NixPath>(name: &P) -> Result<c_uint> {
@@ -17,6 +18,28 @@ pub fn if_nametoindex<P: ?Sized + NixPath>(name: &P) -> Result<c_uint> {
     }
 }
 
+/// Resolve an interface number into the name of the interface.
+///
+/// ```
+/// # use nix::net::if_::{if_indextoname, if_nametoindex};
+/// # #[cfg(any(target_os = "android", target_os = "linux"))]
+/// # let lo = "lo";
+/// # #[cfg(not(any(target_os = "android", target_os = "linux")))]
+/// # let lo = "lo0";
+/// let index = if_nametoindex(lo).unwrap();
+/// assert_eq!(if_indextoname(index).unwrap().to_str().unwrap(), lo);
+/// ```
+pub fn if_indextoname(index: c_uint) -> Result<CString> {
+    let mut buf = [0 as libc::c_char; libc::IF_NAMESIZE];
+    let ret = unsafe { libc::if_indextoname(index, buf.as_mut_ptr()) };
+
+    if ret.is_null() {
+        Err(Error::last())
+    } else {
+        Ok(unsafe { CStr::from_ptr(buf.as_ptr()) }.to_owned())
+    }
+}
+
 libc_bitflags!(
     /// Standard interface flags, used by `getifaddrs`
     pub struct InterfaceFlags: libc::c_int {
@@ -338,7 +361,6 @@ libc_bitflags!(
 mod if_nameindex {
     use super::*;
 
-    use std::ffi::CStr;
     use std::fmt;
     use std::marker::PhantomData;
     use std::ptr::NonNull;
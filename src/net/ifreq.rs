@@ -0,0 +1,160 @@
+//! Network interface configuration with the classic `SIOC*` ioctls.
+//!
+//! Each function takes a socket to issue the ioctl on, which may be any
+//! socket, such as an `AF_INET` datagram socket, and the name of the
+//! interface.  Changing an interface's configuration requires
+//! `CAP_NET_ADMIN`.
+//!
+//! See also [netdevice(7)](https://man7.org/linux/man-pages/man7/netdevice.7.html).
+
+use crate::errno::Errno;
+use crate::net::if_::InterfaceFlags;
+use crate::sys::socket::{SockaddrIn, SockaddrLike};
+use crate::{NixPath, Result};
+use libc::{self, c_char, c_int, c_short, c_ulong};
+use std::mem;
+use std::os::unix::io::RawFd;
+
+// libc's own ifreq is too recent for nix's minimum libc version.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub(super) struct IfReq {
+    pub(super) ifr_name: [c_char; libc::IFNAMSIZ],
+    pub(super) ifr_ifru: IfrIfru,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub(super) union IfrIfru {
+    addr: libc::sockaddr,
+    hwaddr: libc::sockaddr,
+    pub(super) flags: c_short,
+    mtu: c_int,
+    // struct ifmap, the largest member, is 24 bytes on 64-bit platforms
+    _pad: [u64; 3],
+}
+
+impl IfReq {
+    pub(super) fn new<P: ?Sized + NixPath>(name: &P) -> Result<Self> {
+        // All-zeros is a valid ifreq
+        let mut req: IfReq = unsafe { mem::zeroed() };
+        name.with_nix_path(|name| {
+            let name = name.to_bytes();
+            // Leave room for the terminating NUL
+            if name.len() >= libc::IFNAMSIZ {
+                return Err(Errno::ENAMETOOLONG);
+            }
+            for (dst, &src) in req.ifr_name.iter_mut().zip(name) {
+                *dst = src as c_char;
+            }
+            Ok(())
+        })??;
+        Ok(req)
+    }
+
+    pub(super) fn ioctl(
+        &mut self,
+        fd: RawFd,
+        request: c_ulong,
+    ) -> Result<()> {
+        // The request type differs between libcs
+        let res = unsafe {
+            libc::ioctl(fd, request as _, self as *mut IfReq)
+        };
+        Errno::result(res).map(drop)
+    }
+}
+
+/// Get the MTU of the interface `name`, with `SIOCGIFMTU`.
+pub fn get_mtu<P: ?Sized + NixPath>(fd: RawFd, name: &P) -> Result<c_int> {
+    let mut req = IfReq::new(name)?;
+    req.ioctl(fd, libc::SIOCGIFMTU)?;
+    Ok(unsafe { req.ifr_ifru.mtu })
+}
+
+/// Set the MTU of the interface `name`, with `SIOCSIFMTU`.
+pub fn set_mtu<P: ?Sized + NixPath>(
+    fd: RawFd,
+    name: &P,
+    mtu: c_int,
+) -> Result<()> {
+    let mut req = IfReq::new(name)?;
+    req.ifr_ifru.mtu = mtu;
+    req.ioctl(fd, libc::SIOCSIFMTU)
+}
+
+/// Get the active flags of the interface `name`, with `SIOCGIFFLAGS`.
+///
+/// Only the flags that fit in the ioctl's 16 bit field are reported, so
+/// flags such as `IFF_LOWER_UP` are never set.
+pub fn get_flags<P: ?Sized + NixPath>(
+    fd: RawFd,
+    name: &P,
+) -> Result<InterfaceFlags> {
+    let mut req = IfReq::new(name)?;
+    req.ioctl(fd, libc::SIOCGIFFLAGS)?;
+    // Flags are unsigned, but stored in a short
+    let flags = unsafe { req.ifr_ifru.flags } as u16 as c_int;
+    Ok(InterfaceFlags::from_bits_truncate(flags))
+}
+
+/// Set the flags of the interface `name`, with `SIOCSIFFLAGS`.
+///
+/// This replaces all of the flags, so to bring an interface up, for
+/// example, first read them with [`get_flags`] and then add `IFF_UP`.
+pub fn set_flags<P: ?Sized + NixPath>(
+    fd: RawFd,
+    name: &P,
+    flags: InterfaceFlags,
+) -> Result<()> {
+    let mut req = IfReq::new(name)?;
+    req.ifr_ifru.flags = flags.bits() as c_short;
+    req.ioctl(fd, libc::SIOCSIFFLAGS)
+}
+
+/// Get the hardware address of the interface `name`, with `SIOCGIFHWADDR`.
+///
+/// Returns the hardware type, as one of the `ARPHRD_*` values, and the first
+/// six bytes of the address, which is the whole of an Ethernet MAC address.
+pub fn get_hwaddr<P: ?Sized + NixPath>(
+    fd: RawFd,
+    name: &P,
+) -> Result<(u16, [u8; 6])> {
+    let mut req = IfReq::new(name)?;
+    req.ioctl(fd, libc::SIOCGIFHWADDR)?;
+    let hwaddr = unsafe { req.ifr_ifru.hwaddr };
+    let mut addr = [0u8; 6];
+    for (dst, &src) in addr.iter_mut().zip(hwaddr.sa_data.iter()) {
+        *dst = src as u8;
+    }
+    Ok((hwaddr.sa_family, addr))
+}
+
+/// Get the IPv4 address of the interface `name`, with `SIOCGIFADDR`.
+pub fn get_addr<P: ?Sized + NixPath>(
+    fd: RawFd,
+    name: &P,
+) -> Result<SockaddrIn> {
+    let mut req = IfReq::new(name)?;
+    req.ioctl(fd, libc::SIOCGIFADDR)?;
+    let addr = unsafe { req.ifr_ifru.addr };
+    unsafe {
+        SockaddrIn::from_raw(
+            &addr,
+            Some(mem::size_of::<libc::sockaddr_in>() as libc::socklen_t),
+        )
+    }
+    .ok_or(Errno::EAFNOSUPPORT)
+}
+
+/// Set the IPv4 address of the interface `name`, with `SIOCSIFADDR`.
+pub fn set_addr<P: ?Sized + NixPath>(
+    fd: RawFd,
+    name: &P,
+    addr: &SockaddrIn,
+) -> Result<()> {
+    let mut req = IfReq::new(name)?;
+    // sockaddr_in and sockaddr are the same size
+    req.ifr_ifru.addr = unsafe { *addr.as_ptr() };
+    req.ioctl(fd, libc::SIOCSIFADDR)
+}
@@ -0,0 +1,148 @@
+//! Messages read from BSD routing sockets.
+//!
+//! Open a routing socket with
+//! `socket(AddressFamily::Route, SockType::Raw, SockFlag::empty(), None)`,
+//! then split each buffer read from it into messages with
+//! [`RouteMessages`].
+//!
+//! See also [route(4)](https://www.freebsd.org/cgi/man.cgi?query=route&sektion=4).
+
+use crate::errno::Errno;
+use crate::Result;
+use std::convert::TryFrom;
+use std::mem;
+use std::ptr;
+
+libc_enum! {
+    /// The type of a routing message.
+    #[repr(i32)]
+    #[non_exhaustive]
+    pub enum RouteMessageType {
+        /// A route was added.
+        RTM_ADD,
+        /// A route was deleted.
+        RTM_DELETE,
+        /// A route's gateway, metrics or flags changed.
+        RTM_CHANGE,
+        /// The reply to a route lookup.
+        RTM_GET,
+        /// The kernel suspects that a route is failing.
+        RTM_LOSING,
+        /// The kernel was told to use a different route.
+        RTM_REDIRECT,
+        /// A lookup failed for lack of a route.
+        RTM_MISS,
+        /// An address was added to an interface.
+        RTM_NEWADDR,
+        /// An address was removed from an interface.
+        RTM_DELADDR,
+        /// An interface's state changed, for example it went up or down.
+        RTM_IFINFO,
+        /// An interface arrived or departed.
+        #[cfg(not(target_os = "macos"))]
+        #[cfg_attr(docsrs, doc(cfg(all())))]
+        RTM_IFANNOUNCE,
+    }
+    impl TryFrom<i32>
+}
+
+// msglen, version and type start every routing message
+const HEADER_LEN: usize = 4;
+
+/// A single message read from a routing socket.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct RouteMessage<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> RouteMessage<'a> {
+    /// The version of the routing socket protocol.  The layout of messages
+    /// whose version is not `libc::RTM_VERSION` is unknown.
+    pub fn version(&self) -> u8 {
+        self.buf[2]
+    }
+
+    /// The type of the message, or `None` for types that nix does not know.
+    pub fn message_type(&self) -> Option<RouteMessageType> {
+        RouteMessageType::try_from(i32::from(self.buf[3])).ok()
+    }
+
+    /// The whole message, including its header.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.buf
+    }
+
+    /// Copy the start of the message into a `T`, if it is long enough.
+    fn header<T>(&self) -> Option<T> {
+        if self.version() as libc::c_int != libc::RTM_VERSION
+            || self.buf.len() < mem::size_of::<T>()
+        {
+            return None;
+        }
+        // The buffer is long enough, and these are plain C structs
+        Some(unsafe { ptr::read_unaligned(self.buf.as_ptr() as *const T) })
+    }
+
+    /// The header of an `RTM_IFINFO` message, which describes an interface.
+    pub fn if_msghdr(&self) -> Option<libc::if_msghdr> {
+        match self.message_type() {
+            Some(RouteMessageType::RTM_IFINFO) => self.header(),
+            _ => None,
+        }
+    }
+
+    /// The header of a message about a route, such as `RTM_ADD` or
+    /// `RTM_GET`.  The addresses named by its `rtm_addrs` field follow it.
+    #[cfg(target_os = "macos")]
+    #[cfg_attr(docsrs, doc(cfg(all())))]
+    pub fn rt_msghdr(&self) -> Option<libc::rt_msghdr> {
+        match self.message_type() {
+            Some(RouteMessageType::RTM_ADD)
+            | Some(RouteMessageType::RTM_DELETE)
+            | Some(RouteMessageType::RTM_CHANGE)
+            | Some(RouteMessageType::RTM_GET)
+            | Some(RouteMessageType::RTM_LOSING)
+            | Some(RouteMessageType::RTM_REDIRECT)
+            | Some(RouteMessageType::RTM_MISS) => self.header(),
+            _ => None,
+        }
+    }
+}
+
+/// An iterator over the messages in a buffer read from a routing socket.
+///
+/// If a message's length is inconsistent with the buffer, the iterator
+/// returns `EBADMSG` and then stops.
+#[derive(Clone, Debug)]
+pub struct RouteMessages<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> RouteMessages<'a> {
+    /// Iterate over the messages in `buf`.
+    pub fn new(buf: &'a [u8]) -> Self {
+        RouteMessages { buf }
+    }
+}
+
+impl<'a> Iterator for RouteMessages<'a> {
+    type Item = Result<RouteMessage<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buf.is_empty() {
+            return None;
+        }
+        let len = if self.buf.len() < HEADER_LEN {
+            0
+        } else {
+            u16::from_ne_bytes([self.buf[0], self.buf[1]]) as usize
+        };
+        if len < HEADER_LEN || len > self.buf.len() {
+            self.buf = &[];
+            return Some(Err(Errno::EBADMSG));
+        }
+        let (msg, rest) = self.buf.split_at(len);
+        self.buf = rest;
+        Some(Ok(RouteMessage { buf: msg }))
+    }
+}
@@ -0,0 +1,98 @@
+//! Creating TUN and TAP virtual network devices.
+//!
+//! See also the kernel's [TUN/TAP documentation](https://www.kernel.org/doc/html/latest/networking/tuntap.html).
+
+use crate::errno::Errno;
+use crate::net::ifreq::IfReq;
+use crate::sys::ioctl::ioctl_num_type;
+use crate::{NixPath, Result};
+use libc::{self, c_int, c_short, c_ulong};
+use std::ffi::CStr;
+use std::mem;
+use std::os::unix::io::RawFd;
+
+// libc only recently gained the TUNSET* constants
+const TUNSETIFF: ioctl_num_type =
+    crate::request_code_write!(b'T', 202, mem::size_of::<c_int>());
+const TUNSETPERSIST: ioctl_num_type =
+    crate::request_code_write!(b'T', 203, mem::size_of::<c_int>());
+const TUNSETOWNER: ioctl_num_type =
+    crate::request_code_write!(b'T', 204, mem::size_of::<c_int>());
+const TUNSETGROUP: ioctl_num_type =
+    crate::request_code_write!(b'T', 206, mem::size_of::<c_int>());
+
+libc_bitflags! {
+    /// Flags for [`open`].  Exactly one of `IFF_TUN` and `IFF_TAP` must be
+    /// given.
+    pub struct TunFlags: c_short {
+        /// A TUN device, which carries IP packets.
+        IFF_TUN as c_short;
+        /// A TAP device, which carries Ethernet frames.
+        IFF_TAP as c_short;
+        /// Do not prepend the four byte packet information header to each
+        /// packet.
+        IFF_NO_PI as c_short;
+        /// Allow the device to be opened more than once, with one queue per
+        /// file descriptor.
+        IFF_MULTI_QUEUE as c_short;
+        /// Prepend a `virtio_net_hdr` to each packet.
+        IFF_VNET_HDR as c_short;
+        /// Fail with `EBUSY` rather than attach to an existing device.
+        IFF_TUN_EXCL as c_short;
+    }
+}
+
+/// Create the TUN or TAP device `name`, or attach to it if it already
+/// exists, and return a file descriptor for its packets along with the
+/// device's name.
+///
+/// If `name` is empty or contains a `%d` pattern, such as `"tun%d"`, the
+/// kernel chooses the name.  Creating a device requires `CAP_NET_ADMIN`; the
+/// returned file descriptor is close-on-exec, and closing it removes the
+/// device unless [`set_persist`] was used.
+pub fn open<P: ?Sized + NixPath>(
+    name: &P,
+    flags: TunFlags,
+) -> Result<(RawFd, String)> {
+    let mut req = IfReq::new(name)?;
+    req.ifr_ifru.flags = flags.bits();
+
+    let path = b"/dev/net/tun\0";
+    let fd = unsafe {
+        libc::open(path.as_ptr().cast(), libc::O_RDWR | libc::O_CLOEXEC)
+    };
+    let fd = Errno::result(fd)?;
+    if let Err(e) = req.ioctl(fd, TUNSETIFF as c_ulong) {
+        unsafe { libc::close(fd) };
+        return Err(e);
+    }
+
+    let name = unsafe { CStr::from_ptr(req.ifr_name.as_ptr()) };
+    Ok((fd, name.to_string_lossy().into_owned()))
+}
+
+fn ioctl_int(
+    fd: RawFd,
+    request: ioctl_num_type,
+    value: c_ulong,
+) -> Result<()> {
+    let res = unsafe { libc::ioctl(fd, request, value) };
+    Errno::result(res).map(drop)
+}
+
+/// Keep the device open by `fd` after it is closed, with `TUNSETPERSIST`.
+pub fn set_persist(fd: RawFd, persist: bool) -> Result<()> {
+    ioctl_int(fd, TUNSETPERSIST, persist as c_ulong)
+}
+
+/// Allow the user `owner` to attach to the device open by `fd`, with
+/// `TUNSETOWNER`.
+pub fn set_owner(fd: RawFd, owner: libc::uid_t) -> Result<()> {
+    ioctl_int(fd, TUNSETOWNER, owner as c_ulong)
+}
+
+/// Allow members of the group `group` to attach to the device open by `fd`,
+/// with `TUNSETGROUP`.
+pub fn set_group(fd: RawFd, group: libc::gid_t) -> Result<()> {
+    ioctl_int(fd, TUNSETGROUP, group as c_ulong)
+}
@@ -0,0 +1,168 @@
+//! Listen for the kernel's device hotplug events.
+//!
+//! The kernel broadcasts a uevent whenever a device is added, removed or
+//! changes, on the `NETLINK_KOBJECT_UEVENT` netlink family.  This is what
+//! udev listens to.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use nix::net::uevent::UEventSocket;
+//! # use nix::sys::socket::SockFlag;
+//! let sock = UEventSocket::new(SockFlag::SOCK_CLOEXEC).unwrap();
+//! for event in sock {
+//!     let event = event.unwrap();
+//!     println!("{:?} {:?}", event.action(), event.devpath());
+//! }
+//! ```
+
+use crate::errno::Errno;
+use crate::sys::socket::{
+    bind, recvfrom, socket, AddressFamily, NetlinkAddr, SockFlag, SockProtocol,
+    SockType,
+};
+use crate::unistd;
+use crate::Result;
+use std::ffi::{OsStr, OsString};
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::io::{AsRawFd, IntoRawFd, RawFd};
+
+// The multicast group of events sent by the kernel.  udev rebroadcasts
+// processed events on group 2.
+const KERNEL_GROUP: u32 = 1;
+
+// The kernel's UEVENT_BUFFER_SIZE is 2048, leave plenty of room
+const BUFFER_SIZE: usize = 8192;
+
+/// A device event sent by the kernel.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct UEvent {
+    vars: Vec<(OsString, OsString)>,
+}
+
+impl UEvent {
+    /// Parse a message received from the kernel.
+    ///
+    /// The message is a header of the form `action@devpath`, followed by
+    /// `KEY=value` pairs, each terminated by a NUL byte.  Fails with
+    /// `EBADMSG` if the header is missing, as it is from the messages that
+    /// udev sends.
+    pub fn parse(buf: &[u8]) -> Result<UEvent> {
+        let mut fields =
+            buf.split(|&b| b == 0).filter(|field| !field.is_empty());
+        match fields.next() {
+            Some(header) if header.contains(&b'@') => (),
+            _ => return Err(Errno::EBADMSG),
+        }
+        let vars = fields
+            .filter_map(|field| {
+                let eq = field.iter().position(|&b| b == b'=')?;
+                let key = OsStr::from_bytes(&field[..eq]);
+                let value = OsStr::from_bytes(&field[eq + 1..]);
+                Some((key.to_owned(), value.to_owned()))
+            })
+            .collect();
+        Ok(UEvent { vars })
+    }
+
+    /// The value of the variable `key`, if the event has it.
+    pub fn get<K: AsRef<OsStr>>(&self, key: K) -> Option<&OsStr> {
+        let key = key.as_ref();
+        self.vars
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_os_str())
+    }
+
+    /// What happened to the device, such as `add`, `remove` or `change`.
+    pub fn action(&self) -> Option<&OsStr> {
+        self.get("ACTION")
+    }
+
+    /// The path of the device under `/sys`.
+    pub fn devpath(&self) -> Option<&OsStr> {
+        self.get("DEVPATH")
+    }
+
+    /// The subsystem of the device, such as `block` or `usb`.
+    pub fn subsystem(&self) -> Option<&OsStr> {
+        self.get("SUBSYSTEM")
+    }
+
+    /// Iterate over all of the event's variables, in the order that the
+    /// kernel sent them.
+    pub fn iter(&self) -> impl Iterator<Item = (&OsStr, &OsStr)> {
+        self.vars.iter().map(|(k, v)| (k.as_os_str(), v.as_os_str()))
+    }
+}
+
+/// A socket that receives the kernel's device events.
+///
+/// Iterating over it receives events forever.  On a non-blocking socket, it
+/// yields `EAGAIN` when no event is pending.
+#[derive(Debug, Eq, Hash, PartialEq)]
+pub struct UEventSocket(RawFd);
+
+impl UEventSocket {
+    /// Open a socket and subscribe it to the kernel's device events.
+    ///
+    /// `flags` may contain `SOCK_CLOEXEC` and `SOCK_NONBLOCK`.
+    pub fn new(flags: SockFlag) -> Result<UEventSocket> {
+        let fd = socket(
+            AddressFamily::Netlink,
+            SockType::Datagram,
+            flags,
+            SockProtocol::NetlinkKObjectUEvent,
+        )?;
+        // Close the socket if binding fails
+        let sock = UEventSocket(fd);
+        bind(fd, &NetlinkAddr::new(0, KERNEL_GROUP))?;
+        Ok(sock)
+    }
+
+    /// Receive the next event.
+    ///
+    /// Messages sent by other processes than the kernel are ignored, so
+    /// that they can't forge events.
+    pub fn recv(&self) -> Result<UEvent> {
+        let mut buf = [0u8; BUFFER_SIZE];
+        loop {
+            let (len, addr) = recvfrom::<NetlinkAddr>(self.0, &mut buf)?;
+            if addr.map_or(false, |addr| addr.pid() == 0) {
+                return UEvent::parse(&buf[..len]);
+            }
+        }
+    }
+}
+
+impl AsRawFd for UEventSocket {
+    fn as_raw_fd(&self) -> RawFd {
+        self.0
+    }
+}
+
+impl IntoRawFd for UEventSocket {
+    fn into_raw_fd(self) -> RawFd {
+        let fd = self.0;
+        std::mem::forget(self);
+        fd
+    }
+}
+
+impl Drop for UEventSocket {
+    fn drop(&mut self) {
+        // As in PtyMaster, closing an invalid fd indicates a double close
+        let e = unistd::close(self.0);
+        if e == Err(Errno::EBADF) {
+            panic!("Closing an invalid file descriptor!");
+        };
+    }
+}
+
+impl Iterator for UEventSocket {
+    type Item = Result<UEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.recv())
+    }
+}
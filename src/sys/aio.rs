@@ -26,14 +26,27 @@
 #[cfg(target_os = "freebsd")]
 use std::io::{IoSlice, IoSliceMut};
 use std::{
+    collections::VecDeque,
     convert::TryFrom,
     fmt::{self, Debug},
+    io,
     marker::{PhantomData, PhantomPinned},
     mem,
+    ops::RangeInclusive,
     os::unix::io::RawFd,
     pin::Pin,
     ptr,
+    sync::{
+        atomic::{AtomicIsize, AtomicUsize, Ordering},
+        mpsc, Arc, Condvar, Mutex, Weak,
+    },
     thread,
+    time::{Duration, Instant},
+};
+#[cfg(feature = "aio-future")]
+use std::{
+    future::Future,
+    task::{Context, Poll},
 };
 
 use libc::{c_void, off_t};
@@ -91,6 +104,108 @@ pub enum AioCancelStat {
     AioAllDone = libc::AIO_ALLDONE,
 }
 
+impl AioCancelStat {
+    /// Convert into a `Result`, treating any outcome that leaves requests
+    /// still running as an error.
+    ///
+    /// `AioCanceled` and `AioAllDone` both map to `Ok(())`, while
+    /// `AioNotCanceled` is returned as the `Err` value.  This is convenient
+    /// for teardown paths that must wait for outstanding operations before
+    /// proceeding.
+    pub fn into_result(self) -> std::result::Result<(), AioCancelStat> {
+        match self {
+            AioCancelStat::AioCanceled | AioCancelStat::AioAllDone => Ok(()),
+            AioCancelStat::AioNotCanceled => Err(self),
+        }
+    }
+}
+
+/// The outcome of a reaped AIO operation, as returned by
+/// [`Aio::reap`](trait.Aio.html#method.reap).
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum AioOutcome<T> {
+    /// The operation ran to completion, with the given result.
+    Completed(T),
+    /// The operation was canceled before it ran.
+    Canceled,
+}
+
+/// The kind of I/O performed by an AIO operation, as returned by
+/// [`Aio::op`](trait.Aio.html#tymethod.op).
+///
+/// Vectored operations are reported as `Read` or `Write`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum AioOp {
+    /// Read from the file into memory
+    Read,
+    /// Write from memory to the file
+    Write,
+    /// Sync the file's data to disk, and possibly its metadata too
+    Fsync(AioFsyncMode),
+}
+
+/// Lifecycle state of an AIO operation, as returned by
+/// [`Aio::state`](trait.Aio.html#tymethod.state).
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum AioCbState {
+    /// The operation has not yet been submitted to the kernel.
+    NotSubmitted,
+    /// The operation has been submitted and has not yet completed.
+    InFlight,
+    /// The operation has completed, but its status has not yet been collected
+    /// with [`Aio::aio_return`](trait.Aio.html#tymethod.aio_return).
+    Completed,
+    /// The operation was canceled, but its status has not yet been collected
+    /// with [`Aio::aio_return`](trait.Aio.html#tymethod.aio_return), which
+    /// will report `ECANCELED`.
+    Canceled,
+    /// The operation has completed and `aio_return` has been called.  It no
+    /// longer has any in-kernel state.
+    Reaped,
+}
+
+/// A point-in-time description of an AIO operation, as returned by
+/// [`Aio::snapshot`](trait.Aio.html#tymethod.snapshot).
+///
+/// Unlike the `Debug` output, this includes the operation's live status, and
+/// is intended for logging and telemetry.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct AioSnapshot {
+    /// The file descriptor
+    pub fd:       RawFd,
+    /// File offset of the operation
+    pub offset:   off_t,
+    /// Requested length of the operation, in bytes
+    pub nbytes:   usize,
+    /// True if the operation reads into a buffer
+    pub is_read:  bool,
+    /// True if the operation writes from a buffer
+    pub is_write: bool,
+    /// The operation's priority
+    pub priority: i32,
+    /// Lifecycle state of the operation
+    pub state:    AioCbState,
+    /// The result of `aio_error`, or `None` if the operation is not in the
+    /// kernel's hands
+    pub error:    Option<Result<()>>,
+}
+
+/// Coarse progress of an AIO operation, as returned by
+/// [`Aio::progress`](trait.Aio.html#method.progress).
+///
+/// POSIX provides no portable way to learn how many bytes an in-flight
+/// operation has transferred, so this only reports whether it has started and
+/// whether it has finished.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum AioProgress {
+    /// The operation has not been submitted.
+    NotStarted,
+    /// The operation has been submitted, and is still running.
+    InFlight,
+    /// The operation has finished, whether or not it has been reaped.
+    Done,
+}
+
 /// Newtype that adds Send and Sync to libc::aiocb, which contains raw pointers
 #[repr(transparent)]
 struct LibcAiocb(libc::aiocb);
@@ -98,6 +213,57 @@ struct LibcAiocb(libc::aiocb);
 unsafe impl Send for LibcAiocb {}
 unsafe impl Sync for LibcAiocb {}
 
+fn aio_error(aiocb: &libc::aiocb) -> Result<()> {
+    let r = unsafe { libc::aio_error(aiocb) };
+    match r {
+        0 => Ok(()),
+        num if num > 0 => Err(Errno::from_i32(num)),
+        -1 => Err(Errno::last()),
+        num => panic!("unknown aio_error return value {:?}", num),
+    }
+}
+
+/// After `lio_listio` failed as a whole, did it still accept this operation?
+/// It did if the operation is still running or has actually completed.
+fn lio_accepted(aiocb: &mut libc::aiocb) -> bool {
+    if aiocb.aio_lio_opcode == libc::LIO_NOP {
+        return false;
+    }
+    match unsafe { libc::aio_error(aiocb) } {
+        // An invalid aiocb was never queued
+        -1 => false,
+        libc::EAGAIN => false,
+        // glibc also refuses to enqueue operations with an invalid priority.
+        // An operation that really ran and failed with EINVAL transferred
+        // nothing, so it can safely be submitted again.
+        #[cfg(all(target_os = "linux", target_env = "gnu"))]
+        libc::EINVAL => false,
+        // glibc reports success for operations that it never enqueued, so
+        // require that some data was transferred, too.  An operation that
+        // transferred nothing can safely be submitted again.  glibc's
+        // aio_return merely reads the aiocb, so this doesn't reap it.
+        #[cfg(all(target_os = "linux", target_env = "gnu"))]
+        0 => unsafe { libc::aio_return(aiocb) > 0 },
+        _ => true,
+    }
+}
+
+/// Check the alignment requirements of direct I/O, in debug builds only.
+fn debug_assert_aligned(
+    buf: *const u8,
+    offs: off_t,
+    len: usize,
+    alignment: usize,
+) {
+    debug_assert!(
+        alignment.is_power_of_two(),
+        "alignment must be a power of two"
+    );
+    debug_assert_eq!(buf as usize % alignment, 0, "buffer is misaligned");
+    debug_assert_eq!(offs as usize % alignment, 0, "offset is misaligned");
+    debug_assert_eq!(len % alignment, 0, "length is misaligned");
+}
+
 /// Base class for all AIO operations.  Should only be used directly when
 /// checking for completion.
 // We could create some kind of AsPinnedMut trait, and implement it for all aio
@@ -108,7 +274,10 @@ unsafe impl Sync for LibcAiocb {}
 #[repr(C)]
 struct AioCb {
     aiocb:       LibcAiocb,
-    /// Could this `AioCb` potentially have any in-kernel state?
+    /// Could this `AioCb` potentially have any in-kernel state?  Set on
+    /// submission, and cleared once the kernel is known to be finished with
+    /// the operation: by `aio_return`, or by a cancellation after which the
+    /// operation is no longer running.
     // It would be really nice to perform the in-progress check entirely at
     // compile time.  But I can't figure out how, because:
     // * Future::poll takes a `Pin<&mut self>` rather than `self`, and
@@ -116,13 +285,22 @@ struct AioCb {
     //   that there's no way to write an AioCb constructor that neither boxes
     //   the object itself, nor moves it during return.
     in_progress: bool,
+    /// Has the operation ever been submitted?
+    submitted:   bool,
+    /// Has `aio_return` been called since the last submission?
+    reaped:      bool,
+    /// Unique identifier, assigned at construction
+    id:          usize,
 }
 
+/// Source of [`Aio::id`] values
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
 impl AioCb {
     pin_utils::unsafe_unpinned!(aiocb: LibcAiocb);
 
     fn aio_return(mut self: Pin<&mut Self>) -> Result<usize> {
-        self.in_progress = false;
+        self.as_mut().set_reaped();
         unsafe {
             let p: *mut libc::aiocb = &mut self.aiocb.0;
             Errno::result(libc::aio_return(p))
@@ -134,13 +312,17 @@ impl AioCb {
         let r = unsafe {
             libc::aio_cancel(self.aiocb.0.aio_fildes, &mut self.aiocb.0)
         };
-        match r {
-            libc::AIO_CANCELED => Ok(AioCancelStat::AioCanceled),
-            libc::AIO_NOTCANCELED => Ok(AioCancelStat::AioNotCanceled),
-            libc::AIO_ALLDONE => Ok(AioCancelStat::AioAllDone),
-            -1 => Err(Errno::last()),
+        let stat = match r {
+            libc::AIO_CANCELED => AioCancelStat::AioCanceled,
+            libc::AIO_NOTCANCELED => AioCancelStat::AioNotCanceled,
+            libc::AIO_ALLDONE => AioCancelStat::AioAllDone,
+            -1 => return Err(Errno::last()),
             _ => panic!("unknown aio_cancel return value"),
+        };
+        if stat != AioCancelStat::AioNotCanceled {
+            self.set_finished();
         }
+        Ok(stat)
     }
 
     fn common_init(fd: RawFd, prio: i32, sigev_notify: SigevNotify) -> Self {
@@ -155,25 +337,136 @@ impl AioCb {
         AioCb {
             aiocb:       LibcAiocb(a),
             in_progress: false,
+            submitted:   false,
+            reaped:      false,
+            id:          NEXT_ID.fetch_add(1, Ordering::Relaxed),
         }
     }
 
+    /// Has the operation been submitted, but not yet reaped?  Unlike
+    /// `in_progress`, this remains true after a successful cancellation.
+    fn awaiting_return(&self) -> bool {
+        self.submitted && !self.reaped
+    }
+
     fn error(self: Pin<&mut Self>) -> Result<()> {
-        let r = unsafe { libc::aio_error(&self.aiocb().0) };
-        match r {
-            0 => Ok(()),
-            num if num > 0 => Err(Errno::from_i32(num)),
-            -1 => Err(Errno::last()),
-            num => panic!("unknown aio_error return value {:?}", num),
-        }
+        aio_error(&self.aiocb().0)
     }
 
     fn in_progress(&self) -> bool {
         self.in_progress
     }
 
+    /// Has the kernel certainly finished with the operation's buffers?
+    /// `in_progress` alone isn't enough, because operations submitted with
+    /// [`lio_listio`] through `AsMut` never set it.
+    fn buffer_released(&self) -> bool {
+        !self.in_progress && aio_error(&self.aiocb.0) != Err(Errno::EINPROGRESS)
+    }
+
+    fn is_read(&self) -> bool {
+        match self.aiocb.0.aio_lio_opcode {
+            libc::LIO_READ => true,
+            #[cfg(target_os = "freebsd")]
+            libc::LIO_READV => true,
+            _ => false,
+        }
+    }
+
+    fn is_write(&self) -> bool {
+        match self.aiocb.0.aio_lio_opcode {
+            libc::LIO_WRITE => true,
+            #[cfg(target_os = "freebsd")]
+            libc::LIO_WRITEV => true,
+            _ => false,
+        }
+    }
+
+    /// Record that the kernel is no longer using the operation's buffers.  It
+    /// must still be reaped.
+    fn set_finished(mut self: Pin<&mut Self>) {
+        self.in_progress = false;
+    }
+
+    /// Record that the operation has been reaped, whether by `aio_return` or
+    /// by some other means.
+    fn set_reaped(mut self: Pin<&mut Self>) {
+        self.in_progress = false;
+        self.reaped = true;
+    }
+
     fn set_in_progress(mut self: Pin<&mut Self>) {
         self.as_mut().in_progress = true;
+        self.as_mut().submitted = true;
+        self.as_mut().reaped = false;
+    }
+
+    /// Submit the operation, dispatching on its stored `aio_lio_opcode` in the
+    /// same way that `lio_listio` would.
+    fn submit(mut self: Pin<&mut Self>) -> Result<()> {
+        check_priority(self.aiocb.0.aio_reqprio)?;
+        let p: *mut libc::aiocb = &mut self.as_mut().aiocb().0;
+        let r = unsafe {
+            match (*p).aio_lio_opcode {
+                libc::LIO_READ => libc::aio_read(p),
+                libc::LIO_WRITE => libc::aio_write(p),
+                #[cfg(target_os = "freebsd")]
+                libc::LIO_READV => libc::aio_readv(p),
+                #[cfg(target_os = "freebsd")]
+                libc::LIO_WRITEV => libc::aio_writev(p),
+                _ => return Err(Errno::EINVAL),
+            }
+        };
+        Errno::result(r).map(|_| self.set_in_progress())
+    }
+
+    fn completion_error(self: Pin<&mut Self>) -> Option<Errno> {
+        if !self.awaiting_return() {
+            return None;
+        }
+        match self.error() {
+            Ok(()) | Err(Errno::EINPROGRESS) => None,
+            Err(e) => Some(e),
+        }
+    }
+
+    fn state(self: Pin<&mut Self>) -> AioCbState {
+        if !self.submitted {
+            AioCbState::NotSubmitted
+        } else if self.reaped {
+            AioCbState::Reaped
+        } else {
+            match self.error() {
+                Err(Errno::EINPROGRESS) => AioCbState::InFlight,
+                Err(Errno::ECANCELED) => AioCbState::Canceled,
+                _ => AioCbState::Completed,
+            }
+        }
+    }
+
+    fn snapshot(mut self: Pin<&mut Self>) -> AioSnapshot {
+        let error = if self.awaiting_return() {
+            Some(self.as_mut().error())
+        } else {
+            None
+        };
+        let state = match error {
+            None if self.reaped => AioCbState::Reaped,
+            None => AioCbState::NotSubmitted,
+            Some(Err(Errno::EINPROGRESS)) => AioCbState::InFlight,
+            Some(Err(Errno::ECANCELED)) => AioCbState::Canceled,
+            Some(_) => AioCbState::Completed,
+        };
+        AioSnapshot {
+            fd: self.aiocb.0.aio_fildes,
+            offset: self.aiocb.0.aio_offset,
+            nbytes: self.aiocb.0.aio_nbytes,
+            is_read: self.is_read(),
+            is_write: self.is_write(),
+            priority: self.aiocb.0.aio_reqprio,
+            state,
+            error,
+        }
     }
 
     /// Update the notification settings for an existing AIO operation that has
@@ -182,11 +475,15 @@ impl AioCb {
     // normally called before the object needs to be pinned, that is, before
     // it's been submitted to the kernel.
     fn set_sigev_notify(&mut self, sigev_notify: SigevNotify) {
+        self.set_sigevent(&SigEvent::new(sigev_notify))
+    }
+
+    fn set_sigevent(&mut self, sigevent: &SigEvent) {
         assert!(
             !self.in_progress,
             "Can't change notification settings for an in-progress operation"
         );
-        self.aiocb.0.aio_sigevent = SigEvent::new(sigev_notify).sigevent();
+        self.aiocb.0.aio_sigevent = sigevent.sigevent();
     }
 }
 
@@ -195,6 +492,9 @@ impl Debug for AioCb {
         fmt.debug_struct("AioCb")
             .field("aiocb", &self.aiocb.0)
             .field("in_progress", &self.in_progress)
+            .field("submitted", &self.submitted)
+            .field("reaped", &self.reaped)
+            .field("id", &self.id)
             .finish()
     }
 }
@@ -210,8 +510,37 @@ impl Drop for AioCb {
     }
 }
 
+mod private {
+    use std::pin::Pin;
+
+    pub trait AioPriv {
+        /// Record that the kernel is no longer using the operation's buffers,
+        /// without reaping it.
+        fn set_finished(self: Pin<&mut Self>);
+
+        /// Record that the operation was reaped without
+        /// [`Aio::aio_return`](super::Aio::aio_return).
+        fn set_reaped(self: Pin<&mut Self>);
+    }
+}
+
+macro_rules! aio_priv_methods {
+    () => {
+        fn set_finished(self: Pin<&mut Self>) {
+            self.aiocb().set_finished()
+        }
+
+        fn set_reaped(self: Pin<&mut Self>) {
+            self.aiocb().set_reaped()
+        }
+    };
+}
+
 /// Methods common to all AIO operations
-pub trait Aio {
+///
+/// This trait is sealed: it can't be implemented outside of Nix, so that new
+/// methods may be added to it without breaking downstream code.
+pub trait Aio: private::AioPriv {
     /// The return type of [`Aio::aio_return`].
     type Output;
 
@@ -234,6 +563,12 @@ pub trait Aio {
     /// result and handle operations that were not canceled or that have already
     /// completed.
     ///
+    /// If the result is `AioCanceled` or `AioAllDone`, the operation is no
+    /// longer considered in progress, and may be dropped.  It should still be
+    /// reaped with [`Aio::aio_return`] to release any resources held by the
+    /// operating system.  Until then, [`Aio::state`] reports it as
+    /// `Canceled` or `Completed`.
+    ///
     /// # Examples
     ///
     /// Cancel an outstanding aio operation.  Note that we must still call
@@ -309,13 +644,24 @@ pub trait Aio {
     /// [aio_error](https://pubs.opengroup.org/onlinepubs/9699919799/functions/aio_error.html)
     fn error(self: Pin<&mut Self>) -> Result<()>;
 
+    /// Returns the error with which a completed operation failed, if any.
+    ///
+    /// Returns `None` if the operation is still in progress, if it succeeded,
+    /// or if it has not been submitted.  Unlike [`Aio::error`], this never
+    /// conflates the operation's own status with a failure of the `aio_error`
+    /// call.  It must be called before [`Aio::aio_return`], since afterwards
+    /// the operation no longer has a status.
+    fn completion_error(self: Pin<&mut Self>) -> Option<Errno>;
+
     /// Returns the underlying file descriptor associated with the operation.
     fn fd(&self) -> RawFd;
 
     /// Does this operation currently have any in-kernel state?
     ///
     /// Dropping an operation that does have in-kernel state constitutes a
-    /// resource leak.
+    /// resource leak.  An operation that was successfully canceled is no
+    /// longer in progress, but its status must still be collected with
+    /// [`Aio::aio_return`].
     ///
     /// # Examples
     ///
@@ -341,6 +687,63 @@ pub trait Aio {
     /// ```
     fn in_progress(&self) -> bool;
 
+    /// Returns an identifier that is unique to this operation.
+    ///
+    /// The identifier is assigned when the operation is constructed, and
+    /// does not depend on its address, so it remains the same if the
+    /// operation is moved before being pinned.  That makes it suitable as a
+    /// key for tracking outstanding operations, for example in a reactor.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use nix::sys::aio::*;
+    /// # use nix::sys::signal::SigevNotify;
+    /// let aiof0 = AioFsync::new(0, AioFsyncMode::O_SYNC, 0,
+    ///     SigevNotify::SigevNone);
+    /// let aiof1 = AioFsync::new(0, AioFsyncMode::O_SYNC, 0,
+    ///     SigevNotify::SigevNone);
+    /// assert_ne!(aiof0.id(), aiof1.id());
+    /// ```
+    fn id(&self) -> usize;
+
+    /// Does this operation read data into memory?
+    ///
+    /// True for [`AioRead`] and `AioReadv`, false otherwise.
+    fn is_read(&self) -> bool;
+
+    /// Does this operation write data from memory?
+    ///
+    /// True for [`AioWrite`] and `AioWritev`, false otherwise.
+    fn is_write(&self) -> bool;
+
+    /// Returns the kind of I/O that this operation performs.
+    ///
+    /// Every `Aio` implementor is submitted the same way, with
+    /// [`Aio::submit`], so this is mainly useful for code that handles
+    /// several kinds of operation generically and needs to record which is
+    /// which.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use nix::sys::aio::*;
+    /// # use nix::sys::signal::SigevNotify;
+    /// let aiof = AioFsync::new(0, AioFsyncMode::O_SYNC, 0,
+    ///     SigevNotify::SigevNone);
+    /// assert_eq!(aiof.op(), AioOp::Fsync(AioFsyncMode::O_SYNC));
+    /// ```
+    fn op(&self) -> AioOp;
+
+    /// Has [`Aio::aio_return`] been called since the operation was last
+    /// submitted?
+    ///
+    /// Calling `aio_return` twice for the same submission is undefined, so
+    /// helpers that may share responsibility for reaping can use this to
+    /// avoid doing it twice.  This is true even if `aio_return` returned an
+    /// error.
+    fn is_reaped(&self) -> bool;
+
     /// Returns the priority of the `AioCb`
     fn priority(&self) -> i32;
 
@@ -348,14 +751,313 @@ pub trait Aio {
     /// not yet been submitted.
     fn set_sigev_notify(&mut self, sev: SigevNotify);
 
+    /// Like [`Aio::set_sigev_notify`], but use an existing `SigEvent`.
+    ///
+    /// This allows a single `SigEvent` to be built once and shared by a whole
+    /// batch of operations, guaranteeing that they all notify the same way.
+    /// The `SigEvent` is copied into the operation, so it need not outlive
+    /// it.  However, whatever the `SigEvent` refers to, such as a kqueue,
+    /// must remain valid until the operation completes.
+    fn set_sigevent(&mut self, sigevent: &SigEvent);
+
     /// Returns the `SigEvent` that will be used for notification.
     fn sigevent(&self) -> SigEvent;
 
+    /// Borrow the raw `sigevent` that will be used for notification.
+    ///
+    /// Unlike [`Aio::sigevent`], this doesn't construct a new `SigEvent`.
+    fn raw_sigevent(&self) -> &libc::sigevent;
+
+    /// Returns the kind of notification configured for this operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use nix::sys::aio::*;
+    /// # use nix::sys::signal::{SigevNotify, SigevNotifyKind};
+    /// let aiof = AioFsync::new(0, AioFsyncMode::O_SYNC, 0,
+    ///     SigevNotify::SigevNone);
+    /// assert_eq!(aiof.notify_kind(), SigevNotifyKind::SigevNone);
+    /// ```
+    fn notify_kind(&self) -> SigevNotifyKind {
+        // Every constructor and set_sigev_notify go through SigevNotify, so
+        // the raw value is always one it can describe.
+        self.sigevent()
+            .notify_kind()
+            .expect("aio_sigevent has an unknown sigev_notify")
+    }
+
+    /// Builder-style version of [`Aio::set_sigev_notify`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use nix::sys::aio::*;
+    /// # use nix::sys::signal::{Signal, SigevNotify};
+    /// let sev = SigevNotify::SigevSignal {
+    ///     signal: Signal::SIGUSR2,
+    ///     si_value: 0
+    /// };
+    /// let aiof = AioFsync::new(0, AioFsyncMode::O_SYNC, 0,
+    ///     SigevNotify::SigevNone)
+    ///     .with_sigev_notify(sev);
+    /// assert_eq!(aiof.sigevent().sigevent().sigev_signo,
+    ///     Signal::SIGUSR2 as i32);
+    /// ```
+    fn with_sigev_notify(mut self, sev: SigevNotify) -> Self
+    where
+        Self: Sized,
+    {
+        self.set_sigev_notify(sev);
+        self
+    }
+
+    /// Builder-style version of [`Aio::set_sigevent`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use nix::sys::aio::*;
+    /// # use nix::sys::signal::{SigEvent, Signal, SigevNotify};
+    /// let sigevent = SigEvent::new(SigevNotify::SigevSignal {
+    ///     signal: Signal::SIGUSR2,
+    ///     si_value: 0
+    /// });
+    /// let wbuf = [0; 4];
+    /// let batch = (0..4).map(|i| {
+    ///     AioWrite::new(0, i * 4, &wbuf, 0, SigevNotify::SigevNone)
+    ///         .with_sigevent(&sigevent)
+    /// }).collect::<Vec<_>>();
+    /// ```
+    fn with_sigevent(mut self, sigevent: &SigEvent) -> Self
+    where
+        Self: Sized,
+    {
+        self.set_sigevent(sigevent);
+        self
+    }
+
+    /// Like [`Aio::aio_return`], but report cancellation separately from
+    /// failure.
+    ///
+    /// After an operation is successfully canceled with [`Aio::cancel`],
+    /// `aio_return` fails with `ECANCELED`.  During shutdown that's expected,
+    /// not an I/O error, so this method returns it as
+    /// [`AioOutcome::Canceled`] instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use nix::sys::aio::*;
+    /// # use nix::sys::signal::SigevNotify;
+    /// # use std::os::unix::io::AsRawFd;
+    /// # use tempfile::tempfile;
+    /// let f = tempfile().unwrap();
+    /// let mut aiow = Box::pin(AioWrite::new(f.as_raw_fd(), 0, b"abcd", 0,
+    ///     SigevNotify::SigevNone));
+    /// aiow.as_mut().submit().unwrap();
+    /// aiow.as_mut().cancel().unwrap();
+    /// aio_suspend(&[&*aiow], None).unwrap();
+    /// match aiow.as_mut().reap() {
+    ///     Ok(AioOutcome::Completed(n)) => assert_eq!(n, 4),
+    ///     Ok(AioOutcome::Canceled) => (),
+    ///     Err(e) => panic!("aio_write failed: {:?}", e)
+    /// }
+    /// ```
+    fn reap(mut self: Pin<&mut Self>) -> Result<AioOutcome<Self::Output>> {
+        // Some implementations don't set errno when aio_return fails, so
+        // check aio_error first.
+        let canceled = self.as_mut().error() == Err(Errno::ECANCELED);
+        match self.aio_return() {
+            Ok(r) => Ok(AioOutcome::Completed(r)),
+            Err(_) if canceled => Ok(AioOutcome::Canceled),
+            Err(Errno::ECANCELED) => Ok(AioOutcome::Canceled),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Returns a coarse indication of the operation's progress, suitable for
+    /// display.
+    ///
+    /// This is a simplified view of [`Aio::state`] that doesn't distinguish
+    /// between completed and reaped operations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use nix::sys::aio::*;
+    /// # use nix::sys::signal::SigevNotify;
+    /// let mut aiof = Box::pin(AioFsync::new(0, AioFsyncMode::O_SYNC, 0,
+    ///     SigevNotify::SigevNone));
+    /// assert_eq!(aiof.as_mut().progress(), AioProgress::NotStarted);
+    /// ```
+    fn progress(self: Pin<&mut Self>) -> AioProgress {
+        match self.state() {
+            AioCbState::NotSubmitted => AioProgress::NotStarted,
+            AioCbState::InFlight => AioProgress::InFlight,
+            AioCbState::Completed
+            | AioCbState::Canceled
+            | AioCbState::Reaped => AioProgress::Done,
+        }
+    }
+
+    /// Returns the operation's position in its lifecycle.
+    ///
+    /// Unlike [`Aio::in_progress`], this distinguishes between an operation
+    /// that was never submitted and one that has already been reaped, and
+    /// between one that is still running and one that has completed or been
+    /// canceled but not yet been reaped.  The latter distinction requires a
+    /// call to `aio_error`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use nix::errno::Errno;
+    /// # use nix::sys::aio::*;
+    /// # use nix::sys::signal::SigevNotify::SigevNone;
+    /// # use std::{thread, time};
+    /// # use std::os::unix::io::AsRawFd;
+    /// # use tempfile::tempfile;
+    /// let f = tempfile().unwrap();
+    /// let mut aiof = Box::pin(AioFsync::new(f.as_raw_fd(), AioFsyncMode::O_SYNC,
+    ///     0, SigevNone));
+    /// assert_eq!(aiof.as_mut().state(), AioCbState::NotSubmitted);
+    /// aiof.as_mut().submit().expect("aio_fsync failed early");
+    /// while (aiof.as_mut().state() == AioCbState::InFlight) {
+    ///     thread::sleep(time::Duration::from_millis(10));
+    /// }
+    /// assert_eq!(aiof.as_mut().state(), AioCbState::Completed);
+    /// aiof.as_mut().aio_return().expect("aio_fsync failed late");
+    /// assert_eq!(aiof.as_mut().state(), AioCbState::Reaped);
+    /// ```
+    fn state(self: Pin<&mut Self>) -> AioCbState;
+
+    /// Returns a description of the operation, including its live status.
+    ///
+    /// While the operation is in progress, this calls `aio_error`.  It is
+    /// meant for logging and diagnostics.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use nix::sys::aio::*;
+    /// # use nix::sys::signal::SigevNotify;
+    /// const WBUF: &[u8] = b"abcdef123456";
+    /// let mut aiow = Box::pin(AioWrite::new(1001, 2, WBUF, 0,
+    ///     SigevNotify::SigevNone));
+    /// let snap = aiow.as_mut().snapshot();
+    /// assert_eq!(snap.offset, 2);
+    /// assert_eq!(snap.nbytes, WBUF.len());
+    /// assert!(snap.is_write);
+    /// assert_eq!(snap.state, AioCbState::NotSubmitted);
+    /// assert_eq!(snap.error, None);
+    /// ```
+    fn snapshot(self: Pin<&mut Self>) -> AioSnapshot;
+
     /// Actually start the I/O operation.
     ///
     /// After calling this method and until [`Aio::aio_return`] returns `Ok`,
     /// the structure may not be moved in memory.
+    ///
+    /// Fails with `EINVAL`, without calling into the kernel, if the
+    /// operation's priority lies outside of [`priority_range`].
     fn submit(self: Pin<&mut Self>) -> Result<()>;
+
+    /// Block until this operation completes, a signal is delivered, or the
+    /// timeout has passed.
+    ///
+    /// This is a convenience wrapper around [`aio_suspend`] for a single
+    /// operation.  It does not reap the operation; the caller must still call
+    /// [`Aio::aio_return`].  If `timeout` is `None`, it will block
+    /// indefinitely.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use nix::sys::aio::*;
+    /// # use nix::sys::signal::SigevNotify;
+    /// # use std::os::unix::io::AsRawFd;
+    /// # use tempfile::tempfile;
+    /// const WBUF: &[u8] = b"abcdef123456";
+    /// let mut f = tempfile().unwrap();
+    /// let mut aiocb = Box::pin(AioWrite::new(f.as_raw_fd(),
+    ///     2,   //offset
+    ///     WBUF,
+    ///     0,   //priority
+    ///     SigevNotify::SigevNone));
+    /// aiocb.as_mut().submit().unwrap();
+    /// aiocb.wait(None).expect("aio_suspend failed");
+    /// assert_eq!(aiocb.as_mut().aio_return().unwrap(), WBUF.len());
+    /// ```
+    fn wait(&self, timeout: Option<TimeSpec>) -> Result<()>;
+
+    /// Like [`Aio::submit`], but first check that the file descriptor is open.
+    ///
+    /// Some platforms, like Linux, only report a bad file descriptor after
+    /// the operation has been queued, which makes it hard to tell where the
+    /// error came from.  This method fails with `EBADF` up front instead.  It
+    /// costs an extra system call, so `submit` remains the fast path.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use nix::errno::Errno;
+    /// # use nix::sys::aio::*;
+    /// # use nix::sys::signal::SigevNotify;
+    /// let mut aiow = Box::pin(AioWrite::new(-1, 0, b"abcd", 0,
+    ///     SigevNotify::SigevNone));
+    /// assert_eq!(aiow.as_mut().checked_submit(), Err(Errno::EBADF));
+    /// ```
+    fn checked_submit(self: Pin<&mut Self>) -> Result<()> {
+        Errno::result(unsafe { libc::fcntl(self.fd(), libc::F_GETFD) })?;
+        self.submit()
+    }
+
+    /// Like [`Aio::submit`], but retry up to `retries` times if the kernel's
+    /// AIO queue is full.
+    ///
+    /// Submission fails with `EAGAIN` when a system-wide or per-process AIO
+    /// limit is temporarily exhausted.  This method sleeps for `backoff`
+    /// after each such failure and then tries again.  Any other error is
+    /// returned immediately, as is `EAGAIN` once the retries are used up.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use nix::errno::Errno;
+    /// # use nix::sys::aio::*;
+    /// # use nix::sys::signal::SigevNotify;
+    /// # use std::{thread, time::Duration};
+    /// # use std::os::unix::io::AsRawFd;
+    /// # use tempfile::tempfile;
+    /// const WBUF: &[u8] = b"abcdef123456";
+    /// let mut f = tempfile().unwrap();
+    /// let mut aiow = Box::pin(AioWrite::new(f.as_raw_fd(), 0, WBUF, 0,
+    ///     SigevNotify::SigevNone));
+    /// aiow.as_mut()
+    ///     .submit_with_retry(10, Duration::from_millis(1))
+    ///     .unwrap();
+    /// while (aiow.as_mut().error() == Err(Errno::EINPROGRESS)) {
+    ///     thread::sleep(Duration::from_millis(10));
+    /// }
+    /// assert_eq!(aiow.as_mut().aio_return().unwrap(), WBUF.len());
+    /// ```
+    fn submit_with_retry(
+        mut self: Pin<&mut Self>,
+        retries: usize,
+        backoff: Duration,
+    ) -> Result<()> {
+        let mut attempts = 0;
+        loop {
+            match self.as_mut().submit() {
+                Err(Errno::EAGAIN) if attempts < retries => {
+                    attempts += 1;
+                    thread::sleep(backoff);
+                }
+                r => return r,
+            }
+        }
+    }
 }
 
 macro_rules! aio_methods {
@@ -364,6 +1066,10 @@ macro_rules! aio_methods {
             self.aiocb().cancel()
         }
 
+        fn completion_error(self: Pin<&mut Self>) -> Option<Errno> {
+            self.aiocb().completion_error()
+        }
+
         fn error(self: Pin<&mut Self>) -> Result<()> {
             self.aiocb().error()
         }
@@ -372,11 +1078,19 @@ macro_rules! aio_methods {
             self.aiocb.aiocb.0.aio_fildes
         }
 
+        fn id(&self) -> usize {
+            self.aiocb.id
+        }
+
         fn in_progress(&self) -> bool {
             self.aiocb.in_progress()
         }
 
-        fn priority(&self) -> i32 {
+        fn is_reaped(&self) -> bool {
+            self.aiocb.reaped
+        }
+
+        fn priority(&self) -> i32 {
             self.aiocb.aiocb.0.aio_reqprio
         }
 
@@ -384,22 +1098,61 @@ macro_rules! aio_methods {
             self.aiocb.set_sigev_notify(sev)
         }
 
+        fn set_sigevent(&mut self, sigevent: &SigEvent) {
+            self.aiocb.set_sigevent(sigevent)
+        }
+
+        fn raw_sigevent(&self) -> &libc::sigevent {
+            &self.aiocb.aiocb.0.aio_sigevent
+        }
+
         fn sigevent(&self) -> SigEvent {
-            SigEvent::from(&self.aiocb.aiocb.0.aio_sigevent)
+            SigEvent::from(self.raw_sigevent())
+        }
+
+        fn snapshot(self: Pin<&mut Self>) -> AioSnapshot {
+            let (is_read, is_write) = (self.is_read(), self.is_write());
+            AioSnapshot {
+                is_read,
+                is_write,
+                ..self.aiocb().snapshot()
+            }
+        }
+
+        fn state(self: Pin<&mut Self>) -> AioCbState {
+            self.aiocb().state()
+        }
+
+        fn wait(&self, timeout: Option<TimeSpec>) -> Result<()> {
+            aio_suspend(&[self as &dyn AsRef<libc::aiocb>], timeout)
         }
     };
-    ($func:ident) => {
+    // For operations whose aio_lio_opcode field describes how to submit them
+    (lio_opcode) => {
         aio_methods!();
 
+        fn is_read(&self) -> bool {
+            self.aiocb.is_read()
+        }
+
+        fn is_write(&self) -> bool {
+            self.aiocb.is_write()
+        }
+
+        fn op(&self) -> AioOp {
+            if self.aiocb.is_read() {
+                AioOp::Read
+            } else {
+                AioOp::Write
+            }
+        }
+
         fn aio_return(self: Pin<&mut Self>) -> Result<<Self as Aio>::Output> {
             self.aiocb().aio_return()
         }
 
-        fn submit(mut self: Pin<&mut Self>) -> Result<()> {
-            let p: *mut libc::aiocb = &mut self.as_mut().aiocb().aiocb.0;
-            Errno::result({ unsafe { libc::$func(p) } }).map(|_| {
-                self.aiocb().set_in_progress();
-            })
+        fn submit(self: Pin<&mut Self>) -> Result<()> {
+            self.aiocb().submit()
         }
     };
 }
@@ -429,9 +1182,11 @@ macro_rules! aio_methods {
 /// aiof.as_mut().aio_return().expect("aio_fsync failed late");
 /// ```
 #[derive(Debug)]
-#[repr(transparent)]
+#[repr(C)]
 pub struct AioFsync {
     aiocb: AioCb,
+    // Stored separately, because submit clears aio_lio_opcode
+    mode:  AioFsyncMode,
     _pin:  PhantomPinned,
 }
 
@@ -440,7 +1195,7 @@ impl AioFsync {
 
     /// Returns the operation's fsync mode: data and metadata or data only?
     pub fn mode(&self) -> AioFsyncMode {
-        AioFsyncMode::try_from(self.aiocb.aiocb.0.aio_lio_opcode).unwrap()
+        self.mode
     }
 
     /// Create a new `AioFsync`.
@@ -460,19 +1215,19 @@ impl AioFsync {
         prio: i32,
         sigev_notify: SigevNotify,
     ) -> Self {
-        let mut aiocb = AioCb::common_init(fd, prio, sigev_notify);
-        // To save some memory, store mode in an unused field of the AioCb.
-        // True it isn't very much memory, but downstream creates will likely
-        // create an enum containing this and other AioCb variants and pack
-        // those enums into data structures like Vec, so it adds up.
-        aiocb.aiocb.0.aio_lio_opcode = mode as libc::c_int;
+        let aiocb = AioCb::common_init(fd, prio, sigev_notify);
         AioFsync {
             aiocb,
+            mode,
             _pin: PhantomPinned,
         }
     }
 }
 
+impl private::AioPriv for AioFsync {
+    aio_priv_methods!();
+}
+
 impl Aio for AioFsync {
     type Output = ();
 
@@ -482,10 +1237,23 @@ impl Aio for AioFsync {
         self.aiocb().aio_return().map(drop)
     }
 
+    // AioFsync doesn't set aio_lio_opcode, so don't interpret it.
+    fn is_read(&self) -> bool {
+        false
+    }
+
+    fn is_write(&self) -> bool {
+        false
+    }
+
+    fn op(&self) -> AioOp {
+        AioOp::Fsync(self.mode)
+    }
+
     fn submit(mut self: Pin<&mut Self>) -> Result<()> {
-        let aiocb = &mut self.as_mut().aiocb().aiocb.0;
-        let mode = mem::replace(&mut aiocb.aio_lio_opcode, 0);
-        let p: *mut libc::aiocb = aiocb;
+        check_priority(self.priority())?;
+        let mode = self.mode as libc::c_int;
+        let p: *mut libc::aiocb = &mut self.as_mut().aiocb().aiocb.0;
         Errno::result(unsafe { libc::aio_fsync(mode, p) }).map(|_| {
             self.aiocb().set_in_progress();
         })
@@ -552,6 +1320,14 @@ pub struct AioRead<'a> {
 impl<'a> AioRead<'a> {
     unsafe_pinned!(aiocb: AioCb);
 
+    /// Reap the operation and return the number of bytes read.
+    ///
+    /// This is equivalent to [`Aio::aio_return`], but makes call sites
+    /// self-documenting.
+    pub fn bytes_read(self: Pin<&mut Self>) -> Result<usize> {
+        self.aio_return()
+    }
+
     /// Returns the requested length of the aio operation in bytes
     ///
     /// This method returns the *requested* length of the operation.  To get the
@@ -592,16 +1368,221 @@ impl<'a> AioRead<'a> {
         }
     }
 
+    /// Create a new `AioRead` with the same offset, priority, and notification
+    /// settings as `self`, but a different file descriptor and buffer.
+    ///
+    /// This allows an existing operation to serve as a template for others.
+    /// The new operation is not in progress, regardless of the state of
+    /// `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use nix::sys::aio::*;
+    /// # use nix::sys::signal::SigevNotify;
+    /// let mut buf0 = [0; 4];
+    /// let mut buf1 = [0; 8];
+    /// let template = AioRead::new(0, 512, &mut buf0, 0,
+    ///     SigevNotify::SigevNone);
+    /// let aior = template.clone_config_with(3, &mut buf1);
+    /// assert_eq!(aior.fd(), 3);
+    /// assert_eq!(aior.offset(), 512);
+    /// assert_eq!(aior.nbytes(), 8);
+    /// ```
+    pub fn clone_config_with<'b>(
+        &self,
+        fd: RawFd,
+        buf: &'b mut [u8],
+    ) -> AioRead<'b> {
+        let mut aior = AioRead::new(
+            fd,
+            self.offset(),
+            buf,
+            self.priority(),
+            SigevNotify::SigevNone,
+        );
+        aior.set_sigevent(&self.sigevent());
+        aior
+    }
+
+    /// Like [`AioRead::new`], but zero the buffer first.
+    ///
+    /// If the read is short, the rest of the buffer will then hold zeros
+    /// rather than whatever data it held before.  This guards against
+    /// leaking stale data when a caller forgets to truncate the buffer to the
+    /// length returned by [`Aio::aio_return`], at the cost of a `memset`.
+    pub fn new_zeroed(
+        fd: RawFd,
+        offs: off_t,
+        buf: &'a mut [u8],
+        prio: i32,
+        sigev_notify: SigevNotify,
+    ) -> Self {
+        buf.fill(0);
+        Self::new(fd, offs, buf, prio, sigev_notify)
+    }
+
+    /// Like [`AioRead::new`], but also check that the buffer's address, the
+    /// file offset, and the buffer's length are all multiples of `alignment`.
+    ///
+    /// This is intended for use with `O_DIRECT` and similar modes, where
+    /// misaligned operations would otherwise fail with `EINVAL` only when they
+    /// are submitted, or even later.  The check is only performed in debug
+    /// builds; release builds skip it for performance.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if `alignment` is not a power of two or if any
+    /// of the above values is misaligned.
+    pub fn new_aligned(
+        fd: RawFd,
+        offs: off_t,
+        buf: &'a mut [u8],
+        prio: i32,
+        sigev_notify: SigevNotify,
+        alignment: usize,
+    ) -> Self {
+        debug_assert_aligned(buf.as_ptr(), offs, buf.len(), alignment);
+        Self::new(fd, offs, buf, prio, sigev_notify)
+    }
+
     /// Returns the file offset of the operation.
     pub fn offset(&self) -> off_t {
         self.aiocb.aiocb.0.aio_offset
     }
+
+    /// Replace the buffer with the result of applying `f` to it.
+    ///
+    /// This lets a completed `AioRead` be reused, for example to process the
+    /// data it read and then read into a different part of the same buffer,
+    /// without being rebuilt.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the operation is still in progress.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use nix::sys::aio::*;
+    /// # use nix::sys::signal::SigevNotify;
+    /// let mut rbuf = [0; 8];
+    /// let mut aior = Box::pin(AioRead::new(0, 0, &mut rbuf, 0,
+    ///     SigevNotify::SigevNone));
+    /// aior.as_mut().map_buffer(|buf| &mut buf[4..]);
+    /// assert_eq!(aior.nbytes(), 4);
+    /// ```
+    pub fn map_buffer<F>(self: Pin<&mut Self>, f: F)
+    where
+        F: FnOnce(&'a mut [u8]) -> &'a mut [u8],
+    {
+        let aiocb = self.aiocb().get_mut();
+        assert!(
+            aiocb.buffer_released(),
+            "Can't change the buffer of an in-progress operation"
+        );
+        let a = &mut aiocb.aiocb.0;
+        // Safe because the AioRead was constructed from a slice with lifetime
+        // 'a, and the kernel isn't using it.
+        let buf = unsafe {
+            std::slice::from_raw_parts_mut(a.aio_buf as *mut u8, a.aio_nbytes)
+        };
+        let buf = f(buf);
+        a.aio_buf = buf.as_mut_ptr() as *mut c_void;
+        a.aio_nbytes = buf.len();
+    }
+
+    /// Borrow the buffer, without consuming the `AioRead`.
+    ///
+    /// Returns `None` while the operation is in progress, because the kernel
+    /// may be writing to the buffer.
+    pub fn data(&self) -> Option<&[u8]> {
+        if !self.aiocb.buffer_released() {
+            return None;
+        }
+        let a = &self.aiocb.aiocb.0;
+        // Safe because the AioRead was constructed from a slice with lifetime
+        // 'a, and the kernel isn't using it.
+        Some(unsafe {
+            std::slice::from_raw_parts(a.aio_buf as *const u8, a.aio_nbytes)
+        })
+    }
+
+    /// Reap the operation, and return how many of the requested bytes were
+    /// not read.
+    ///
+    /// This is a substitute for [`Aio::aio_return`], and like it must be
+    /// called only once.  A nonzero result usually means the read reached the
+    /// end of the file.
+    pub fn remaining(self: Pin<&mut Self>) -> Result<usize> {
+        let requested = self.nbytes();
+        self.aio_return().map(|n| requested.saturating_sub(n))
+    }
+
+    /// Reap the operation, and return the number of bytes read along with the
+    /// part of the buffer that was filled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the operation hasn't completed yet.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use nix::sys::aio::*;
+    /// # use nix::sys::signal::SigevNotify;
+    /// # use std::io::Write;
+    /// # use std::os::unix::io::AsRawFd;
+    /// # use tempfile::tempfile;
+    /// let mut f = tempfile().unwrap();
+    /// f.write_all(b"abcdef").unwrap();
+    /// let mut rbuf = [0; 16];
+    /// let mut aior = Box::pin(AioRead::new(f.as_raw_fd(), 2, &mut rbuf, 0,
+    ///     SigevNotify::SigevNone));
+    /// aior.as_mut().submit().unwrap();
+    /// aio_suspend(&[&*aior], None).unwrap();
+    /// let (len, buf) = aior.finish().unwrap();
+    /// assert_eq!(len, 4);
+    /// assert_eq!(buf, b"cdef");
+    /// ```
+    pub fn finish(mut self: Pin<Box<Self>>) -> Result<(usize, &'a mut [u8])> {
+        assert!(
+            self.as_mut().error() != Err(Errno::EINPROGRESS),
+            "Can't finish an operation that is still in progress"
+        );
+        let len = self.as_mut().aio_return()?;
+        let buf = self.try_into_buffer().unwrap_or_else(|_| unreachable!());
+        Ok((len, &mut buf[..len]))
+    }
+
+    /// Consume the `AioRead` and return the buffer it was reading into.
+    ///
+    /// If the operation may still have in-kernel state, the `AioRead` is
+    /// handed back unchanged in the `Err` variant, so the caller may cancel or
+    /// wait for it.
+    pub fn try_into_buffer(
+        self: Pin<Box<Self>>,
+    ) -> std::result::Result<&'a mut [u8], Pin<Box<Self>>> {
+        if !self.aiocb.buffer_released() {
+            return Err(self);
+        }
+        let a = &self.aiocb.aiocb.0;
+        // Safe because the AioRead was constructed from a slice with lifetime
+        // 'a, and the kernel is done with it.
+        Ok(unsafe {
+            std::slice::from_raw_parts_mut(a.aio_buf as *mut u8, a.aio_nbytes)
+        })
+    }
+}
+
+impl<'a> private::AioPriv for AioRead<'a> {
+    aio_priv_methods!();
 }
 
 impl<'a> Aio for AioRead<'a> {
     type Output = usize;
 
-    aio_methods!(aio_read);
+    aio_methods!(lio_opcode);
 }
 
 impl<'a> AsMut<libc::aiocb> for AioRead<'a> {
@@ -674,6 +1655,14 @@ pub struct AioReadv<'a> {
 impl<'a> AioReadv<'a> {
     unsafe_pinned!(aiocb: AioCb);
 
+    /// Reap the operation and return the number of bytes read.
+    ///
+    /// This is equivalent to [`Aio::aio_return`], but makes call sites
+    /// self-documenting.
+    pub fn bytes_read(self: Pin<&mut Self>) -> Result<usize> {
+        self.aio_return()
+    }
+
     /// Returns the number of buffers the operation will read into.
     pub fn iovlen(&self) -> usize {
         self.aiocb.aiocb.0.aio_nbytes
@@ -719,11 +1708,16 @@ impl<'a> AioReadv<'a> {
     }
 }
 
+#[cfg(target_os = "freebsd")]
+impl<'a> private::AioPriv for AioReadv<'a> {
+    aio_priv_methods!();
+}
+
 #[cfg(target_os = "freebsd")]
 impl<'a> Aio for AioReadv<'a> {
     type Output = usize;
 
-    aio_methods!(aio_readv);
+    aio_methods!(lio_opcode);
 }
 
 #[cfg(target_os = "freebsd")]
@@ -784,6 +1778,14 @@ pub struct AioWrite<'a> {
 impl<'a> AioWrite<'a> {
     unsafe_pinned!(aiocb: AioCb);
 
+    /// Reap the operation and return the number of bytes written.
+    ///
+    /// This is equivalent to [`Aio::aio_return`], but makes call sites
+    /// self-documenting.
+    pub fn bytes_written(self: Pin<&mut Self>) -> Result<usize> {
+        self.aio_return()
+    }
+
     /// Returns the requested length of the aio operation in bytes
     ///
     /// This method returns the *requested* length of the operation.  To get the
@@ -828,16 +1830,181 @@ impl<'a> AioWrite<'a> {
         }
     }
 
+    /// Construct a new `AioWrite` that will write the contents of a string.
+    ///
+    /// This is equivalent to calling [`AioWrite::new`] with `s.as_bytes()`.
+    /// No copy is made, so the string must outlive the `AioWrite`.
+    pub fn from_str(
+        fd: RawFd,
+        offs: off_t,
+        s: &'a str,
+        prio: i32,
+        sigev_notify: SigevNotify,
+    ) -> Self {
+        Self::new(fd, offs, s.as_bytes(), prio, sigev_notify)
+    }
+
+    /// Create a new `AioWrite` with the same offset, priority, and
+    /// notification settings as `self`, but a different file descriptor and
+    /// buffer.
+    ///
+    /// This allows an existing operation to serve as a template for others.
+    /// The new operation is not in progress, regardless of the state of
+    /// `self`.
+    pub fn clone_config_with<'b>(
+        &self,
+        fd: RawFd,
+        buf: &'b [u8],
+    ) -> AioWrite<'b> {
+        let mut aiow = AioWrite::new(
+            fd,
+            self.offset(),
+            buf,
+            self.priority(),
+            SigevNotify::SigevNone,
+        );
+        aiow.set_sigevent(&self.sigevent());
+        aiow
+    }
+
+    /// Like [`AioWrite::new`], but also check that the buffer's address, the
+    /// file offset, and the buffer's length are all multiples of `alignment`.
+    ///
+    /// This is intended for use with `O_DIRECT` and similar modes, where
+    /// misaligned operations would otherwise fail with `EINVAL` only when they
+    /// are submitted, or even later.  The check is only performed in debug
+    /// builds; release builds skip it for performance.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if `alignment` is not a power of two or if any
+    /// of the above values is misaligned.
+    pub fn new_aligned(
+        fd: RawFd,
+        offs: off_t,
+        buf: &'a [u8],
+        prio: i32,
+        sigev_notify: SigevNotify,
+        alignment: usize,
+    ) -> Self {
+        debug_assert_aligned(buf.as_ptr(), offs, buf.len(), alignment);
+        Self::new(fd, offs, buf, prio, sigev_notify)
+    }
+
     /// Returns the file offset of the operation.
     pub fn offset(&self) -> off_t {
         self.aiocb.aiocb.0.aio_offset
     }
+
+    /// Replace the buffer with the result of applying `f` to it.
+    ///
+    /// This lets a completed `AioWrite` be reused to write different data
+    /// without being rebuilt.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the operation is still in progress.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use nix::sys::aio::*;
+    /// # use nix::sys::signal::SigevNotify;
+    /// const WBUF: &[u8] = b"abcdef";
+    /// let mut aiow = Box::pin(AioWrite::new(0, 0, WBUF, 0,
+    ///     SigevNotify::SigevNone));
+    /// aiow.as_mut().map_buffer(|buf| &buf[..2]);
+    /// assert_eq!(aiow.nbytes(), 2);
+    /// ```
+    pub fn map_buffer<F>(self: Pin<&mut Self>, f: F)
+    where
+        F: FnOnce(&'a [u8]) -> &'a [u8],
+    {
+        let aiocb = self.aiocb().get_mut();
+        assert!(
+            aiocb.buffer_released(),
+            "Can't change the buffer of an in-progress operation"
+        );
+        let a = &mut aiocb.aiocb.0;
+        // Safe because the AioWrite was constructed from a slice with lifetime
+        // 'a.
+        let buf = unsafe {
+            std::slice::from_raw_parts(a.aio_buf as *const u8, a.aio_nbytes)
+        };
+        let buf = f(buf);
+        // As in AioWrite::new, the pointer is never written through.
+        a.aio_buf = buf.as_ptr() as *mut c_void;
+        a.aio_nbytes = buf.len();
+    }
+
+    /// Borrow the buffer, without consuming the `AioWrite`.
+    ///
+    /// Unlike [`AioRead::data`], this is available even while the operation
+    /// is in progress, since the kernel only reads from the buffer.
+    pub fn data(&self) -> &[u8] {
+        let a = &self.aiocb.aiocb.0;
+        // Safe because the AioWrite was constructed from a slice with lifetime
+        // 'a.
+        unsafe {
+            std::slice::from_raw_parts(a.aio_buf as *const u8, a.aio_nbytes)
+        }
+    }
+
+    /// Reap the operation, and return how many of the requested bytes were
+    /// not written.
+    ///
+    /// This is a substitute for [`Aio::aio_return`], and like it must be
+    /// called only once.  A nonzero result usually indicates a problem, like
+    /// a full disk, that resubmitting the rest won't fix.
+    pub fn remaining(self: Pin<&mut Self>) -> Result<usize> {
+        let requested = self.nbytes();
+        self.aio_return().map(|n| requested.saturating_sub(n))
+    }
+
+    /// Reap the operation, and return the number of bytes written along with
+    /// the buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the operation hasn't completed yet.
+    pub fn finish(mut self: Pin<Box<Self>>) -> Result<(usize, &'a [u8])> {
+        assert!(
+            self.as_mut().error() != Err(Errno::EINPROGRESS),
+            "Can't finish an operation that is still in progress"
+        );
+        let len = self.as_mut().aio_return()?;
+        let buf = self.try_into_buffer().unwrap_or_else(|_| unreachable!());
+        Ok((len, buf))
+    }
+
+    /// Consume the `AioWrite` and return the buffer it was writing from.
+    ///
+    /// If the operation may still have in-kernel state, the `AioWrite` is
+    /// handed back unchanged in the `Err` variant, so the caller may cancel or
+    /// wait for it.
+    pub fn try_into_buffer(
+        self: Pin<Box<Self>>,
+    ) -> std::result::Result<&'a [u8], Pin<Box<Self>>> {
+        if !self.aiocb.buffer_released() {
+            return Err(self);
+        }
+        let a = &self.aiocb.aiocb.0;
+        // Safe because the AioWrite was constructed from a slice with lifetime
+        // 'a.
+        Ok(unsafe {
+            std::slice::from_raw_parts(a.aio_buf as *const u8, a.aio_nbytes)
+        })
+    }
+}
+
+impl<'a> private::AioPriv for AioWrite<'a> {
+    aio_priv_methods!();
 }
 
 impl<'a> Aio for AioWrite<'a> {
     type Output = usize;
 
-    aio_methods!(aio_write);
+    aio_methods!(lio_opcode);
 }
 
 impl<'a> AsMut<libc::aiocb> for AioWrite<'a> {
@@ -852,42 +2019,567 @@ impl<'a> AsRef<libc::aiocb> for AioWrite<'a> {
     }
 }
 
-/// Asynchronously writes from a scatter/gather list of buffers to a file descriptor.
+/// A buffer that can be owned by an [`AioReadOwned`] or [`AioWriteOwned`].
 ///
-/// # References
+/// # Safety
 ///
-/// [aio_writev](https://www.freebsd.org/cgi/man.cgi?query=aio_writev)
+/// The memory described by `stable_ptr` and `stable_len` must remain valid,
+/// and at the same address, for as long as the buffer is alive, even if the
+/// buffer value itself is moved.  Both methods must always return the same
+/// values.
+pub unsafe trait AioBuf: 'static {
+    /// Returns a pointer to the start of the buffer.
+    fn stable_ptr(&self) -> *const u8;
+
+    /// Returns the length of the buffer, in bytes.
+    fn stable_len(&self) -> usize;
+}
+
+/// A buffer that an [`AioReadOwned`] can read into.
+///
+/// # Safety
+///
+/// In addition to the requirements of [`AioBuf`], the memory must not be
+/// accessible through any other path while the buffer is owned by an
+/// operation, and `stable_mut_ptr` must return the same pointer as
+/// `stable_ptr`.
+pub unsafe trait AioBufMut: AioBuf {
+    /// Returns a mutable pointer to the start of the buffer.
+    fn stable_mut_ptr(&mut self) -> *mut u8;
+}
+
+unsafe impl AioBuf for Box<[u8]> {
+    fn stable_ptr(&self) -> *const u8 {
+        self.as_ptr()
+    }
+
+    fn stable_len(&self) -> usize {
+        self.len()
+    }
+}
+
+unsafe impl AioBufMut for Box<[u8]> {
+    fn stable_mut_ptr(&mut self) -> *mut u8 {
+        self.as_mut_ptr()
+    }
+}
+
+unsafe impl AioBuf for Vec<u8> {
+    fn stable_ptr(&self) -> *const u8 {
+        self.as_ptr()
+    }
+
+    fn stable_len(&self) -> usize {
+        self.len()
+    }
+}
+
+unsafe impl AioBufMut for Vec<u8> {
+    fn stable_mut_ptr(&mut self) -> *mut u8 {
+        self.as_mut_ptr()
+    }
+}
+
+unsafe impl AioBuf for Arc<[u8]> {
+    fn stable_ptr(&self) -> *const u8 {
+        self.as_ptr()
+    }
+
+    fn stable_len(&self) -> usize {
+        self.len()
+    }
+}
+
+unsafe impl AioBuf for &'static [u8] {
+    fn stable_ptr(&self) -> *const u8 {
+        self.as_ptr()
+    }
+
+    fn stable_len(&self) -> usize {
+        self.len()
+    }
+}
+
+unsafe impl AioBuf for &'static mut [u8] {
+    fn stable_ptr(&self) -> *const u8 {
+        self.as_ptr()
+    }
+
+    fn stable_len(&self) -> usize {
+        self.len()
+    }
+}
+
+unsafe impl AioBufMut for &'static mut [u8] {
+    fn stable_mut_ptr(&mut self) -> *mut u8 {
+        self.as_mut_ptr()
+    }
+}
+
+/// Asynchronously reads from a file descriptor into a buffer that it owns.
+///
+/// This is like [`AioRead`], but takes ownership of its buffer instead of
+/// borrowing one, so it has no lifetime parameter.  The buffer may be any
+/// type that implements [`AioBufMut`], such as a `Box<[u8]>` or a `Vec<u8>`.
+/// Retrieve it with [`AioReadOwned::try_into_buf`] once the operation has
+/// been reaped.
+///
+/// If an `AioReadOwned` is dropped while in progress, or while it is still
+/// running after being submitted with [`lio_listio`], its buffer is leaked
+/// rather than freed, because the kernel may still be using it.
 ///
 /// # Examples
 ///
-#[cfg_attr(fbsd14, doc = " ```")]
-#[cfg_attr(not(fbsd14), doc = " ```no_run")]
-/// # use nix::errno::Errno;
-/// # use nix::Error;
+/// ```
 /// # use nix::sys::aio::*;
 /// # use nix::sys::signal::SigevNotify;
-/// # use std::{thread, time};
-/// # use std::io::IoSlice;
+/// # use std::io::Write;
 /// # use std::os::unix::io::AsRawFd;
 /// # use tempfile::tempfile;
-/// const wbuf0: &[u8] = b"abcdef";
-/// const wbuf1: &[u8] = b"123456";
-/// let len = wbuf0.len() + wbuf1.len();
-/// let wbufs = [IoSlice::new(wbuf0), IoSlice::new(wbuf1)];
 /// let mut f = tempfile().unwrap();
-/// let mut aiow = Box::pin(
-///     AioWritev::new(
-///         f.as_raw_fd(),
-///         2,   //offset
-///         &wbufs,
-///         0,   //priority
-///         SigevNotify::SigevNone
-///     )
-/// );
-/// aiow.as_mut().submit().unwrap();
-/// while (aiow.as_mut().error() == Err(Errno::EINPROGRESS)) {
-///     thread::sleep(time::Duration::from_millis(10));
-/// }
+/// f.write_all(b"abcdef").unwrap();
+/// let mut aior = Box::pin(AioReadOwned::new(f.as_raw_fd(), 2, vec![0; 4],
+///     0, SigevNotify::SigevNone));
+/// aior.as_mut().submit().unwrap();
+/// aio_suspend(&[&*aior], None).unwrap();
+/// assert_eq!(aior.as_mut().aio_return(), Ok(4));
+/// let buf = aior.try_into_buf().unwrap();
+/// assert_eq!(buf, b"cdef");
+/// ```
+#[derive(Debug)]
+pub struct AioReadOwned<B: AioBufMut = Box<[u8]>> {
+    aiocb: AioCb,
+    // Held by raw pointer, so that the buffer is never moved or reborrowed
+    // while the kernel may be using it.  Null once taken.
+    buf:   *mut B,
+    _pin:  PhantomPinned,
+}
+
+// Safe because the buffer is owned, just like a Box<B>
+unsafe impl<B: AioBufMut + Send> Send for AioReadOwned<B> {}
+unsafe impl<B: AioBufMut + Sync> Sync for AioReadOwned<B> {}
+
+impl<B: AioBufMut> AioReadOwned<B> {
+    unsafe_pinned!(aiocb: AioCb);
+
+    /// Create a new `AioReadOwned`, placing the data in `buf`.
+    ///
+    /// The arguments are the same as for [`AioRead::new`], except that the
+    /// buffer is owned.  The whole buffer will be read into.
+    pub fn new(
+        fd: RawFd,
+        offs: off_t,
+        buf: B,
+        prio: i32,
+        sigev_notify: SigevNotify,
+    ) -> Self {
+        // Take the buffer's address only once it has reached its final place
+        let buf = Box::into_raw(Box::new(buf));
+        let mut aiocb = AioCb::common_init(fd, prio, sigev_notify);
+        // Safe because buf was just allocated
+        unsafe {
+            aiocb.aiocb.0.aio_nbytes = (*buf).stable_len();
+            aiocb.aiocb.0.aio_buf = (*buf).stable_mut_ptr() as *mut c_void;
+        }
+        aiocb.aiocb.0.aio_lio_opcode = libc::LIO_READ;
+        aiocb.aiocb.0.aio_offset = offs;
+        AioReadOwned {
+            aiocb,
+            buf,
+            _pin: PhantomPinned,
+        }
+    }
+
+    /// Borrow the buffer, without consuming the `AioReadOwned`.
+    ///
+    /// Returns `None` while the operation is in progress, because the kernel
+    /// may be writing to the buffer.
+    pub fn data(&self) -> Option<&[u8]> {
+        if !self.aiocb.buffer_released() {
+            return None;
+        }
+        let a = &self.aiocb.aiocb.0;
+        // Safe because we own the buffer, and the kernel isn't using it.
+        Some(unsafe {
+            std::slice::from_raw_parts(a.aio_buf as *const u8, a.aio_nbytes)
+        })
+    }
+
+    /// Returns the requested length of the aio operation in bytes
+    pub fn nbytes(&self) -> usize {
+        self.aiocb.aiocb.0.aio_nbytes
+    }
+
+    /// Returns the file offset of the operation.
+    pub fn offset(&self) -> off_t {
+        self.aiocb.aiocb.0.aio_offset
+    }
+
+    /// Consume the `AioReadOwned` and return its buffer.
+    ///
+    /// If the operation may still have in-kernel state, the `AioReadOwned` is
+    /// handed back unchanged in the `Err` variant, so the caller may cancel or
+    /// wait for it.
+    pub fn try_into_buf(
+        mut self: Pin<Box<Self>>,
+    ) -> std::result::Result<B, Pin<Box<Self>>> {
+        if !self.aiocb.buffer_released() {
+            return Err(self);
+        }
+        // Safe because buf isn't structurally pinned, and the kernel is done
+        // with it
+        let this = unsafe { self.as_mut().get_unchecked_mut() };
+        let buf = mem::replace(&mut this.buf, ptr::null_mut());
+        Ok(*unsafe { Box::from_raw(buf) })
+    }
+}
+
+impl AioReadOwned<Box<[u8]>> {
+    /// Like [`AioReadOwned::new`], but take a boxed slice.
+    pub fn from_boxed_slice(
+        fd: RawFd,
+        offs: off_t,
+        buf: Box<[u8]>,
+        prio: i32,
+        sigev_notify: SigevNotify,
+    ) -> Self {
+        Self::new(fd, offs, buf, prio, sigev_notify)
+    }
+
+    /// Like [`AioReadOwned::new`], but convert a `Vec` into a boxed slice.
+    ///
+    /// The read's length is the `Vec`'s length, not its capacity.
+    pub fn from_vec(
+        fd: RawFd,
+        offs: off_t,
+        buf: Vec<u8>,
+        prio: i32,
+        sigev_notify: SigevNotify,
+    ) -> Self {
+        Self::new(fd, offs, buf.into_boxed_slice(), prio, sigev_notify)
+    }
+}
+
+impl<B: AioBufMut> private::AioPriv for AioReadOwned<B> {
+    aio_priv_methods!();
+}
+
+impl<B: AioBufMut> Aio for AioReadOwned<B> {
+    type Output = usize;
+
+    aio_methods!(lio_opcode);
+}
+
+impl<B: AioBufMut> AsMut<libc::aiocb> for AioReadOwned<B> {
+    fn as_mut(&mut self) -> &mut libc::aiocb {
+        &mut self.aiocb.aiocb.0
+    }
+}
+
+impl<B: AioBufMut> AsRef<libc::aiocb> for AioReadOwned<B> {
+    fn as_ref(&self) -> &libc::aiocb {
+        &self.aiocb.aiocb.0
+    }
+}
+
+impl<B: AioBufMut> Drop for AioReadOwned<B> {
+    fn drop(&mut self) {
+        // If the operation is still in progress, then the AioCb's own Drop
+        // will complain.  Leak the buffer, since the kernel may still use it.
+        if !self.buf.is_null() && self.aiocb.buffer_released() {
+            // Safe because the buffer hasn't been taken
+            drop(unsafe { Box::from_raw(self.buf) });
+        }
+    }
+}
+
+/// Asynchronously writes to a file descriptor from a buffer that it owns.
+///
+/// This is like [`AioWrite`], but takes ownership of its buffer instead of
+/// borrowing one, so it has no lifetime parameter.  The buffer may be any
+/// type that implements [`AioBuf`], such as a `Box<[u8]>`, a `Vec<u8>`, or an
+/// `Arc<[u8]>`.  Retrieve it with [`AioWriteOwned::try_into_buf`] once the
+/// operation has been reaped.
+///
+/// If an `AioWriteOwned` is dropped while in progress, or while it is still
+/// running after being submitted with [`lio_listio`], its buffer is leaked
+/// rather than freed, because the kernel may still be using it.
+///
+/// # Examples
+///
+/// ```
+/// # use nix::sys::aio::*;
+/// # use nix::sys::signal::SigevNotify;
+/// # use std::os::unix::io::AsRawFd;
+/// # use std::sync::Arc;
+/// # use tempfile::tempfile;
+/// let f = tempfile().unwrap();
+/// let wbuf: Arc<[u8]> = Arc::from(&b"abcd"[..]);
+/// let mut aiow = Box::pin(AioWriteOwned::new(f.as_raw_fd(), 0, wbuf.clone(),
+///     0, SigevNotify::SigevNone));
+/// aiow.as_mut().submit().unwrap();
+/// aio_suspend(&[&*aiow], None).unwrap();
+/// assert_eq!(aiow.as_mut().aio_return(), Ok(4));
+/// ```
+#[derive(Debug)]
+pub struct AioWriteOwned<B: AioBuf = Box<[u8]>> {
+    aiocb: AioCb,
+    // Held by raw pointer, so that the buffer is never moved while the kernel
+    // may be using it.  Null once taken.
+    buf:   *mut B,
+    _pin:  PhantomPinned,
+}
+
+// Safe because the buffer is owned, just like a Box<B>
+unsafe impl<B: AioBuf + Send> Send for AioWriteOwned<B> {}
+unsafe impl<B: AioBuf + Sync> Sync for AioWriteOwned<B> {}
+
+impl<B: AioBuf> AioWriteOwned<B> {
+    unsafe_pinned!(aiocb: AioCb);
+
+    /// Create a new `AioWriteOwned` that writes the contents of `buf`.
+    ///
+    /// The arguments are the same as for [`AioWrite::new`], except that the
+    /// buffer is owned.
+    pub fn new(
+        fd: RawFd,
+        offs: off_t,
+        buf: B,
+        prio: i32,
+        sigev_notify: SigevNotify,
+    ) -> Self {
+        // Take the buffer's address only once it has reached its final place
+        let buf = Box::into_raw(Box::new(buf));
+        let mut aiocb = AioCb::common_init(fd, prio, sigev_notify);
+        // Safe because buf was just allocated.  As in AioWrite::new, the
+        // kernel will never write through this pointer.
+        unsafe {
+            aiocb.aiocb.0.aio_nbytes = (*buf).stable_len();
+            aiocb.aiocb.0.aio_buf = (*buf).stable_ptr() as *mut c_void;
+        }
+        aiocb.aiocb.0.aio_lio_opcode = libc::LIO_WRITE;
+        aiocb.aiocb.0.aio_offset = offs;
+        AioWriteOwned {
+            aiocb,
+            buf,
+            _pin: PhantomPinned,
+        }
+    }
+
+    /// Borrow the buffer, without consuming the `AioWriteOwned`.
+    pub fn data(&self) -> &[u8] {
+        let a = &self.aiocb.aiocb.0;
+        // Safe because we own the buffer, and the kernel only reads it.
+        unsafe {
+            std::slice::from_raw_parts(a.aio_buf as *const u8, a.aio_nbytes)
+        }
+    }
+
+    /// Returns the requested length of the aio operation in bytes
+    pub fn nbytes(&self) -> usize {
+        self.aiocb.aiocb.0.aio_nbytes
+    }
+
+    /// Returns the file offset of the operation.
+    pub fn offset(&self) -> off_t {
+        self.aiocb.aiocb.0.aio_offset
+    }
+
+    /// Consume the `AioWriteOwned` and return its buffer.
+    ///
+    /// If the operation may still have in-kernel state, the `AioWriteOwned`
+    /// is handed back unchanged in the `Err` variant, so the caller may
+    /// cancel or wait for it.
+    pub fn try_into_buf(
+        mut self: Pin<Box<Self>>,
+    ) -> std::result::Result<B, Pin<Box<Self>>> {
+        if !self.aiocb.buffer_released() {
+            return Err(self);
+        }
+        // Safe because buf isn't structurally pinned, and the kernel is done
+        // with it
+        let this = unsafe { self.as_mut().get_unchecked_mut() };
+        let buf = mem::replace(&mut this.buf, ptr::null_mut());
+        Ok(*unsafe { Box::from_raw(buf) })
+    }
+}
+
+impl AioWriteOwned<Box<[u8]>> {
+    /// Like [`AioWriteOwned::new`], but take a boxed slice.
+    pub fn from_boxed_slice(
+        fd: RawFd,
+        offs: off_t,
+        buf: Box<[u8]>,
+        prio: i32,
+        sigev_notify: SigevNotify,
+    ) -> Self {
+        Self::new(fd, offs, buf, prio, sigev_notify)
+    }
+
+    /// Like [`AioWriteOwned::new`], but convert a `Vec` into a boxed slice.
+    pub fn from_vec(
+        fd: RawFd,
+        offs: off_t,
+        buf: Vec<u8>,
+        prio: i32,
+        sigev_notify: SigevNotify,
+    ) -> Self {
+        Self::new(fd, offs, buf.into_boxed_slice(), prio, sigev_notify)
+    }
+}
+
+impl<B: AioBuf> private::AioPriv for AioWriteOwned<B> {
+    aio_priv_methods!();
+}
+
+impl<B: AioBuf> Aio for AioWriteOwned<B> {
+    type Output = usize;
+
+    aio_methods!(lio_opcode);
+}
+
+impl<B: AioBuf> AsMut<libc::aiocb> for AioWriteOwned<B> {
+    fn as_mut(&mut self) -> &mut libc::aiocb {
+        &mut self.aiocb.aiocb.0
+    }
+}
+
+impl<B: AioBuf> AsRef<libc::aiocb> for AioWriteOwned<B> {
+    fn as_ref(&self) -> &libc::aiocb {
+        &self.aiocb.aiocb.0
+    }
+}
+
+impl<B: AioBuf> Drop for AioWriteOwned<B> {
+    fn drop(&mut self) {
+        // If the operation is still in progress, then the AioCb's own Drop
+        // will complain.  Leak the buffer, since the kernel may still use it.
+        if !self.buf.is_null() && self.aiocb.buffer_released() {
+            // Safe because the buffer hasn't been taken
+            drop(unsafe { Box::from_raw(self.buf) });
+        }
+    }
+}
+
+/// Builds AIO operations from named settings, as an alternative to the
+/// positional constructors.
+///
+/// Each setting has a default: offset 0, priority 0, and no notification.
+/// The kind of operation is chosen by the terminal method, such as
+/// [`AioBuilder::read`] or [`AioBuilder::write`], which also supplies the
+/// buffer.  That way a read can only ever be given a mutable buffer.  A
+/// builder may be reused to create several operations with the same
+/// settings.
+///
+/// # Examples
+///
+/// ```
+/// # use nix::sys::aio::*;
+/// # use nix::sys::signal::SigevNotify;
+/// let builder = AioBuilder::new(0)
+///     .offset(512)
+///     .sigev_notify(SigevNotify::SigevNone);
+/// let mut rbuf = [0; 4];
+/// let aior = builder.read(&mut rbuf);
+/// assert_eq!(aior.offset(), 512);
+/// let aiow = builder.offset(1024).write(b"abcd");
+/// assert_eq!(aiow.offset(), 1024);
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct AioBuilder {
+    fd:           RawFd,
+    offs:         off_t,
+    prio:         i32,
+    sigev_notify: SigevNotify,
+}
+
+impl AioBuilder {
+    /// Start building an operation on file descriptor `fd`.
+    pub fn new(fd: RawFd) -> Self {
+        AioBuilder {
+            fd,
+            offs: 0,
+            prio: 0,
+            sigev_notify: SigevNotify::SigevNone,
+        }
+    }
+
+    /// Set the file descriptor.
+    pub fn fd(mut self, fd: RawFd) -> Self {
+        self.fd = fd;
+        self
+    }
+
+    /// Set the file offset.  Ignored by [`AioBuilder::fsync`].
+    pub fn offset(mut self, offs: off_t) -> Self {
+        self.offs = offs;
+        self
+    }
+
+    /// Set the priority.  See [`AioRead::new`] for its meaning.
+    pub fn priority(mut self, prio: i32) -> Self {
+        self.prio = prio;
+        self
+    }
+
+    /// Set how completion will be notified.
+    pub fn sigev_notify(mut self, sigev_notify: SigevNotify) -> Self {
+        self.sigev_notify = sigev_notify;
+        self
+    }
+
+    /// Build an [`AioFsync`].
+    pub fn fsync(&self, mode: AioFsyncMode) -> AioFsync {
+        AioFsync::new(self.fd, mode, self.prio, self.sigev_notify)
+    }
+
+    /// Build an [`AioRead`] that reads into `buf`.
+    pub fn read<'a>(&self, buf: &'a mut [u8]) -> AioRead<'a> {
+        AioRead::new(self.fd, self.offs, buf, self.prio, self.sigev_notify)
+    }
+
+    /// Build an [`AioWrite`] that writes from `buf`.
+    pub fn write<'a>(&self, buf: &'a [u8]) -> AioWrite<'a> {
+        AioWrite::new(self.fd, self.offs, buf, self.prio, self.sigev_notify)
+    }
+}
+
+/// Asynchronously writes from a scatter/gather list of buffers to a file descriptor.
+///
+/// # References
+///
+/// [aio_writev](https://www.freebsd.org/cgi/man.cgi?query=aio_writev)
+///
+/// # Examples
+///
+#[cfg_attr(fbsd14, doc = " ```")]
+#[cfg_attr(not(fbsd14), doc = " ```no_run")]
+/// # use nix::errno::Errno;
+/// # use nix::Error;
+/// # use nix::sys::aio::*;
+/// # use nix::sys::signal::SigevNotify;
+/// # use std::{thread, time};
+/// # use std::io::IoSlice;
+/// # use std::os::unix::io::AsRawFd;
+/// # use tempfile::tempfile;
+/// const wbuf0: &[u8] = b"abcdef";
+/// const wbuf1: &[u8] = b"123456";
+/// let len = wbuf0.len() + wbuf1.len();
+/// let wbufs = [IoSlice::new(wbuf0), IoSlice::new(wbuf1)];
+/// let mut f = tempfile().unwrap();
+/// let mut aiow = Box::pin(
+///     AioWritev::new(
+///         f.as_raw_fd(),
+///         2,   //offset
+///         &wbufs,
+///         0,   //priority
+///         SigevNotify::SigevNone
+///     )
+/// );
+/// aiow.as_mut().submit().unwrap();
+/// while (aiow.as_mut().error() == Err(Errno::EINPROGRESS)) {
+///     thread::sleep(time::Duration::from_millis(10));
+/// }
 /// assert_eq!(aiow.as_mut().aio_return().unwrap(), len);
 /// ```
 #[cfg(target_os = "freebsd")]
@@ -903,6 +2595,14 @@ pub struct AioWritev<'a> {
 impl<'a> AioWritev<'a> {
     unsafe_pinned!(aiocb: AioCb);
 
+    /// Reap the operation and return the number of bytes written.
+    ///
+    /// This is equivalent to [`Aio::aio_return`], but makes call sites
+    /// self-documenting.
+    pub fn bytes_written(self: Pin<&mut Self>) -> Result<usize> {
+        self.aio_return()
+    }
+
     /// Returns the number of buffers the operation will read into.
     pub fn iovlen(&self) -> usize {
         self.aiocb.aiocb.0.aio_nbytes
@@ -952,11 +2652,16 @@ impl<'a> AioWritev<'a> {
     }
 }
 
+#[cfg(target_os = "freebsd")]
+impl<'a> private::AioPriv for AioWritev<'a> {
+    aio_priv_methods!();
+}
+
 #[cfg(target_os = "freebsd")]
 impl<'a> Aio for AioWritev<'a> {
     type Output = usize;
 
-    aio_methods!(aio_writev);
+    aio_methods!(lio_opcode);
 }
 
 #[cfg(target_os = "freebsd")]
@@ -1020,22 +2725,482 @@ pub fn aio_cancel_all(fd: RawFd) -> Result<AioCancelStat> {
     }
 }
 
-/// Suspends the calling process until at least one of the specified operations
-/// have completed, a signal is delivered, or the timeout has passed.
+/// Cancels outstanding AIO requests for a given file descriptor, and reports
+/// the resulting status of each of a list of operations.
 ///
-/// If `timeout` is `None`, `aio_suspend` will block indefinitely.
+/// This combines the file descriptor-wide atomicity of [`aio_cancel_all`] with
+/// the per-operation detail of [`Aio::cancel`].  The second element of the
+/// return value holds the result of `aio_error` for each element of `list`,
+/// in order: `EINPROGRESS` for operations that survived the cancellation,
+/// `ECANCELED` for operations that were canceled, and their completion status
+/// for operations that had already finished.  Entries whose opcode is
+/// `LIO_NOP` were never submitted, so they are reported as `Ok(())`.
 ///
-/// # Examples
+/// As with [`Aio::cancel`], operations that are no longer running afterwards
+/// are no longer considered in progress, so they may be dropped.  But every
+/// operation that was submitted should still be reaped with
+/// [`Aio::aio_return`], even if it was canceled.
 ///
-/// Use `aio_suspend` to block until an aio operation completes.
+/// # Examples
 ///
 /// ```
+/// # use nix::errno::Errno;
 /// # use nix::sys::aio::*;
 /// # use nix::sys::signal::SigevNotify;
 /// # use std::os::unix::io::AsRawFd;
 /// # use tempfile::tempfile;
-/// const WBUF: &[u8] = b"abcdef123456";
-/// let mut f = tempfile().unwrap();
+/// let f = tempfile().unwrap();
+/// let mut aiow = Box::pin(AioWrite::new(f.as_raw_fd(), 0, b"abcd", 0,
+///     SigevNotify::SigevNone));
+/// aiow.as_mut().submit().unwrap();
+/// let (_, status) = aio_cancel_all_with_status(f.as_raw_fd(),
+///     &mut [aiow.as_mut()]).unwrap();
+/// if status[0] == Err(Errno::EINPROGRESS) {
+///     aiow.wait(None).unwrap();
+/// }
+/// // Must call `aio_return`, but ignore the result
+/// let _ = aiow.as_mut().aio_return();
+/// ```
+///
+/// # References
+///
+/// [`aio_cancel`](https://pubs.opengroup.org/onlinepubs/9699919799/functions/aio_cancel.html)
+pub fn aio_cancel_all_with_status<T>(
+    fd: RawFd,
+    list: &mut [Pin<&mut T>],
+) -> Result<(AioCancelStat, Vec<Result<()>>)>
+where
+    T: Aio + ?Sized,
+{
+    let stat = aio_cancel_all(fd)?;
+    let status = list
+        .iter_mut()
+        .map(|aiocb| {
+            // Only a LIO_NOP entry neither reads, writes, nor syncs
+            let nop = !aiocb.is_read()
+                && !aiocb.is_write()
+                && !matches!(aiocb.op(), AioOp::Fsync(_));
+            if nop {
+                return Ok(());
+            }
+            let status = aiocb.as_mut().error();
+            if aiocb.in_progress() && status != Err(Errno::EINPROGRESS) {
+                private::AioPriv::set_finished(aiocb.as_mut());
+            }
+            status
+        })
+        .collect();
+    Ok((stat, status))
+}
+
+/// Counts how many of a list of operations on `fd` are still in progress.
+///
+/// There is no system call that reports the number of outstanding operations
+/// on a file descriptor, so this scans a list of operations tracked by the
+/// caller.  Operations on other file descriptors are ignored.  It is useful
+/// for deciding when a file descriptor may safely be closed.
+///
+/// # Examples
+///
+/// ```
+/// # use nix::sys::aio::*;
+/// # use nix::sys::signal::SigevNotify;
+/// # use std::os::unix::io::AsRawFd;
+/// # use tempfile::tempfile;
+/// let f = tempfile().unwrap();
+/// let mut aiow = Box::pin(AioWrite::new(f.as_raw_fd(), 0, b"abcd", 0,
+///     SigevNotify::SigevNone));
+/// aiow.as_mut().submit().unwrap();
+/// while pending_count(f.as_raw_fd(), &[&*aiow]) > 0 {
+///     aio_suspend(&[&*aiow], None).unwrap();
+/// }
+/// aiow.as_mut().aio_return().unwrap();
+/// ```
+pub fn pending_count(fd: RawFd, list: &[&dyn AsRef<libc::aiocb>]) -> usize {
+    list.iter()
+        .map(|aiocb| (*aiocb).as_ref())
+        .filter(|aiocb| {
+            aiocb.aio_fildes == fd
+                && aio_error(aiocb) == Err(Errno::EINPROGRESS)
+        })
+        .count()
+}
+
+// Marks AIO_PRIO_DELTA_MAX as not yet queried
+const PRIO_DELTA_MAX_UNKNOWN: isize = isize::MIN;
+
+// The result of sysconf(_SC_AIO_PRIO_DELTA_MAX), which can't change while the
+// process runs.  Negative if the system doesn't report a limit.
+static PRIO_DELTA_MAX: AtomicIsize = AtomicIsize::new(PRIO_DELTA_MAX_UNKNOWN);
+
+/// Returns the system's `AIO_PRIO_DELTA_MAX`: the maximum amount by which an
+/// AIO operation's priority may be lowered relative to the calling process.
+///
+/// The value is queried with `sysconf(3)`, because no platform defines it as a
+/// compile-time constant, and cached thereafter.  Returns `None` if the system
+/// doesn't report it, or reports 0.  Systems that report 0, like FreeBSD, don't
+/// prioritize AIO at all, and ignore the operation's priority.
+///
+/// # Examples
+///
+/// ```
+/// # use nix::sys::aio::aio_prio_delta_max;
+/// if let Some(max) = aio_prio_delta_max() {
+///     println!("AIO priorities may range from 0 to {}", max);
+/// }
+/// ```
+pub fn aio_prio_delta_max() -> Option<libc::c_int> {
+    let mut max = PRIO_DELTA_MAX.load(Ordering::Relaxed);
+    if max == PRIO_DELTA_MAX_UNKNOWN {
+        let r = unsafe { libc::sysconf(libc::_SC_AIO_PRIO_DELTA_MAX) };
+        // sysconf returns -1 if the limit is indeterminate or unsupported
+        max = if r <= 0 {
+            -1
+        } else {
+            libc::c_int::try_from(r).unwrap_or(libc::c_int::MAX) as isize
+        };
+        // Racing threads will all store the same value
+        PRIO_DELTA_MAX.store(max, Ordering::Relaxed);
+    }
+    if max <= 0 {
+        None
+    } else {
+        Some(max as libc::c_int)
+    }
+}
+
+/// Returns the range of valid values for the `prio` argument of the AIO
+/// constructors.
+///
+/// The upper bound is the system's `AIO_PRIO_DELTA_MAX`, as reported by
+/// [`aio_prio_delta_max`].  If the system doesn't report it, then no upper
+/// bound is enforced, and the range extends to `c_int::MAX`.
+///
+/// # Examples
+///
+/// ```
+/// # use nix::sys::aio::priority_range;
+/// let range = priority_range();
+/// assert!(range.contains(&0));
+/// ```
+pub fn priority_range() -> RangeInclusive<libc::c_int> {
+    0..=aio_prio_delta_max().unwrap_or(libc::c_int::MAX)
+}
+
+/// Check `prio` against [`priority_range`], before submitting an operation
+/// that the kernel would reject anyway.  Negative priorities are always
+/// rejected, but if the system doesn't report `AIO_PRIO_DELTA_MAX`, then no
+/// upper bound is enforced.
+fn check_priority(prio: libc::c_int) -> Result<()> {
+    if priority_range().contains(&prio) {
+        Ok(())
+    } else {
+        Err(Errno::EINVAL)
+    }
+}
+
+/// Optional AIO features, and whether the current platform supports them.
+///
+/// Returned by [`AioCapabilities::query`].  This lets portable code choose a
+/// strategy at runtime instead of duplicating `cfg` checks.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct AioCapabilities {
+    /// Does the system honor the priority of AIO operations?  See
+    /// [`priority_range`].
+    pub prioritized_io:  bool,
+    /// Is `AioFsyncMode::O_DSYNC` available?
+    pub dsync:           bool,
+    /// Is `SigevNotify::SigevKevent` available?
+    pub sigev_kevent:    bool,
+    /// Is `SigevNotify::SigevThreadId` available?
+    pub sigev_thread_id: bool,
+    /// Are the vectored operations, `AioReadv` and `AioWritev`, available?
+    pub vectored:        bool,
+}
+
+impl AioCapabilities {
+    /// Report the AIO features supported by the current platform.
+    ///
+    /// Most fields are determined at compile time, but `prioritized_io` is
+    /// queried from `sysconf(3)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use nix::sys::aio::AioCapabilities;
+    /// let caps = AioCapabilities::query();
+    /// if caps.dsync {
+    ///     // Use AioFsyncMode::O_DSYNC
+    /// }
+    /// ```
+    pub fn query() -> Self {
+        #[cfg(not(target_os = "netbsd"))]
+        let prioritized_io =
+            unsafe { libc::sysconf(libc::_SC_PRIORITIZED_IO) } > 0;
+        #[cfg(target_os = "netbsd")]
+        let prioritized_io = false;
+
+        AioCapabilities {
+            prioritized_io,
+            dsync: cfg!(any(
+                target_os = "ios",
+                target_os = "linux",
+                target_os = "macos",
+                target_os = "netbsd",
+                target_os = "openbsd"
+            )),
+            sigev_kevent: cfg!(any(
+                target_os = "dragonfly",
+                target_os = "freebsd"
+            )),
+            sigev_thread_id: cfg!(any(
+                target_os = "freebsd",
+                target_os = "linux"
+            )),
+            vectored: cfg!(target_os = "freebsd"),
+        }
+    }
+}
+
+/// Convert the result of an AIO operation into a [`std::io::Result`].
+///
+/// This is convenient when implementing `std::io` traits, or integrating with
+/// an async runtime, on top of this module.
+///
+/// # Examples
+///
+/// ```
+/// # use nix::errno::Errno;
+/// # use nix::sys::aio::into_io_result;
+/// let r = into_io_result::<usize>(Err(Errno::EIO));
+/// assert_eq!(r.unwrap_err().raw_os_error(), Some(Errno::EIO as i32));
+/// ```
+pub fn into_io_result<T>(r: Result<T>) -> io::Result<T> {
+    r.map_err(io::Error::from)
+}
+
+/// Reap every operation in `list` that has completed or been canceled, but not
+/// yet been reaped.
+///
+/// Calling [`Aio::aio_return`] twice on the same operation is undefined
+/// behavior at the OS level, so this function consults each operation's
+/// [`Aio::state`] first.  The result for an operation is `Some` if it was
+/// reaped by this call, or `None` if it had already been reaped, was never
+/// submitted, or is still in flight.  Canceled operations report
+/// `ECANCELED`.
+///
+/// # Examples
+///
+/// ```
+/// # use nix::sys::aio::*;
+/// # use nix::sys::signal::SigevNotify;
+/// # use std::os::unix::io::AsRawFd;
+/// # use std::pin::Pin;
+/// # use tempfile::tempfile;
+/// let f = tempfile().unwrap();
+/// let mut rbuf = [0; 4];
+/// let mut aior = Box::pin(AioRead::new(f.as_raw_fd(), 0, &mut rbuf, 0,
+///     SigevNotify::SigevNone));
+/// let mut aiow = Box::pin(AioWrite::new(f.as_raw_fd(), 8, b"abcd", 0,
+///     SigevNotify::SigevNone));
+/// aior.as_mut().submit().unwrap();
+/// aiow.as_mut().submit().unwrap();
+/// aio_suspend(&[&*aior], None).unwrap();
+/// aior.as_mut().aio_return().unwrap();
+/// aio_suspend(&[&*aiow], None).unwrap();
+/// let mut list = [
+///     aior.as_mut() as Pin<&mut dyn Aio<Output = usize>>,
+///     aiow.as_mut() as Pin<&mut dyn Aio<Output = usize>>,
+/// ];
+/// assert_eq!(reap_pending(&mut list[..]), vec![None, Some(Ok(4))]);
+/// ```
+pub fn reap_pending<T>(
+    list: &mut [Pin<&mut T>],
+) -> Vec<Option<Result<T::Output>>>
+where
+    T: Aio + ?Sized,
+{
+    list.iter_mut()
+        .map(|aiocb| match aiocb.as_mut().state() {
+            AioCbState::Completed | AioCbState::Canceled => {
+                Some(reap_completed(aiocb.as_mut()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Wait for every operation in `list` to complete, and reap them all.
+///
+/// The results are returned in the same order as `list`, regardless of the
+/// order in which the operations completed.  An operation that failed or was
+/// canceled is still reaped, and its error is reported in its slot.  Operations
+/// that were never submitted or were already reaped yield
+/// `Ok(Default::default())`, which is `Ok(0)` for reads and writes.
+///
+/// Unless waiting itself fails, no operation in `list` will be in progress
+/// when this function returns, so they may all be safely dropped.
+///
+/// # Examples
+///
+/// ```
+/// # use nix::sys::aio::*;
+/// # use nix::sys::signal::SigevNotify;
+/// # use std::os::unix::io::AsRawFd;
+/// # use std::pin::Pin;
+/// # use tempfile::tempfile;
+/// let f = tempfile().unwrap();
+/// let mut aiow0 = Box::pin(AioWrite::new(f.as_raw_fd(), 0, b"abcd", 0,
+///     SigevNotify::SigevNone));
+/// let mut aiow1 = Box::pin(AioWrite::new(f.as_raw_fd(), 4, b"ef", 0,
+///     SigevNotify::SigevNone));
+/// aiow0.as_mut().submit().unwrap();
+/// aiow1.as_mut().submit().unwrap();
+/// let mut list = [aiow0.as_mut(), aiow1.as_mut()];
+/// assert_eq!(poll_all(&mut list[..]), vec![Ok(4), Ok(2)]);
+/// ```
+pub fn poll_all<T>(list: &mut [Pin<&mut T>]) -> Vec<Result<T::Output>>
+where
+    T: Aio + ?Sized,
+    T::Output: Default,
+{
+    list.iter_mut()
+        .map(|aiocb| wait_and_reap(aiocb.as_mut()))
+        .collect()
+}
+
+/// Block until `aiocb` is no longer in flight, then reap it if necessary.
+fn wait_and_reap<T>(mut aiocb: Pin<&mut T>) -> Result<T::Output>
+where
+    T: Aio + ?Sized,
+    T::Output: Default,
+{
+    while aiocb.as_mut().state() == AioCbState::InFlight {
+        match aiocb.wait(None) {
+            Ok(()) | Err(Errno::EINTR) => (),
+            Err(e) => return Err(e),
+        }
+    }
+    match aiocb.as_mut().state() {
+        AioCbState::Completed | AioCbState::Canceled => reap_completed(aiocb),
+        _ => Ok(T::Output::default()),
+    }
+}
+
+/// Reap an operation that is known to have completed or been canceled.
+fn reap_completed<T>(mut aiocb: Pin<&mut T>) -> Result<T::Output>
+where
+    T: Aio + ?Sized,
+{
+    // aio_return doesn't necessarily set errno on failure, so collect the
+    // operation's error status first.
+    let status = aiocb.as_mut().error();
+    let r = aiocb.as_mut().aio_return();
+    status.and(r)
+}
+
+feature! {
+#![feature = "aio-future"]
+
+/// Adapts an AIO operation into a [`Future`].
+///
+/// When first polled, the future submits the operation.  It resolves to the
+/// operation's return value once the operation completes, reaping it in the
+/// process.
+///
+/// POSIX AIO has no way to register a waker, so while the operation is in
+/// flight, the future asks to be polled again immediately.  That makes it
+/// usable with any executor, but the executor will busy-spin, keeping a CPU
+/// fully occupied until the operation completes.  Because of that cost, it is
+/// only available with the `aio-future` feature, which is not enabled by
+/// default.  Applications that need efficient notification should use an
+/// event-driven reactor instead, for example via
+/// [`SigevNotify::SigevKevent`](../signal/enum.SigevNotify.html#variant.SigevKevent)
+/// where available.
+///
+/// # Examples
+///
+/// ```
+/// # use nix::sys::aio::*;
+/// # use nix::sys::signal::SigevNotify;
+/// # use std::future::Future;
+/// # use std::os::unix::io::AsRawFd;
+/// # use std::pin::Pin;
+/// # use std::sync::Arc;
+/// # use std::task::{Context, Poll, Wake};
+/// # use tempfile::tempfile;
+/// # struct NoopWaker;
+/// # impl Wake for NoopWaker {
+/// #     fn wake(self: Arc<Self>) {}
+/// # }
+/// let f = tempfile().unwrap();
+/// let mut aiow = Box::pin(AioWrite::new(f.as_raw_fd(), 0, b"abcd", 0,
+///     SigevNotify::SigevNone));
+/// let mut fut = AioFuture::new(aiow.as_mut());
+/// let waker = Arc::new(NoopWaker).into();
+/// let mut cx = Context::from_waker(&waker);
+/// let r = loop {
+///     if let Poll::Ready(r) = Pin::new(&mut fut).poll(&mut cx) {
+///         break r;
+///     }
+/// };
+/// assert_eq!(r, Ok(4));
+/// ```
+#[derive(Debug)]
+pub struct AioFuture<'a, T: ?Sized> {
+    aiocb:     Pin<&'a mut T>,
+    submitted: bool,
+}
+
+impl<'a, T: Aio + ?Sized> AioFuture<'a, T> {
+    /// Create a future that will submit `aiocb` and wait for its completion.
+    pub fn new(aiocb: Pin<&'a mut T>) -> Self {
+        AioFuture {
+            aiocb,
+            submitted: false,
+        }
+    }
+}
+
+impl<'a, T: Aio + ?Sized> Future for AioFuture<'a, T> {
+    type Output = Result<T::Output>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        match self.aiocb.as_mut().state() {
+            AioCbState::NotSubmitted | AioCbState::Reaped => {
+                assert!(!self.submitted, "AioFuture polled after completion");
+                self.submitted = true;
+                if let Err(e) = self.aiocb.as_mut().submit() {
+                    return Poll::Ready(Err(e));
+                }
+            }
+            AioCbState::InFlight => (),
+            AioCbState::Completed | AioCbState::Canceled => {
+                return Poll::Ready(reap_completed(self.aiocb.as_mut()));
+            }
+        }
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+}
+
+/// Suspends the calling process until at least one of the specified operations
+/// have completed, a signal is delivered, or the timeout has passed.
+///
+/// If `timeout` is `None`, `aio_suspend` will block indefinitely.
+///
+/// # Examples
+///
+/// Use `aio_suspend` to block until an aio operation completes.
+///
+/// ```
+/// # use nix::sys::aio::*;
+/// # use nix::sys::signal::SigevNotify;
+/// # use std::os::unix::io::AsRawFd;
+/// # use tempfile::tempfile;
+/// const WBUF: &[u8] = b"abcdef123456";
+/// let mut f = tempfile().unwrap();
 /// let mut aiocb = Box::pin(AioWrite::new(f.as_raw_fd(),
 ///     2,   //offset
 ///     WBUF,
@@ -1052,9 +3217,13 @@ pub fn aio_suspend(
     list: &[&dyn AsRef<libc::aiocb>],
     timeout: Option<TimeSpec>,
 ) -> Result<()> {
-    let p = list as *const [&dyn AsRef<libc::aiocb>]
-        as *const [*const libc::aiocb]
-        as *const *const libc::aiocb;
+    // The elements of list are fat pointers, so they must be converted to an
+    // array of thin pointers for libc.
+    let aiocbs = list
+        .iter()
+        .map(|aiocb| (*aiocb).as_ref() as *const libc::aiocb)
+        .collect::<Vec<_>>();
+    let p = aiocbs.as_ptr();
     let timep = match timeout {
         None => ptr::null::<libc::timespec>(),
         Some(x) => x.as_ref() as *const libc::timespec,
@@ -1063,6 +3232,167 @@ pub fn aio_suspend(
         .map(drop)
 }
 
+/// Like [`aio_suspend`], but automatically retries if interrupted by a signal.
+///
+/// Each retry waits only for whatever remains of the original `timeout`, so
+/// signals never extend the total time spent waiting.  Returns `Ok` once at
+/// least one of the operations has completed, `Err(EAGAIN)` if the timeout
+/// elapses first, or any other error from `aio_suspend`.
+///
+/// # Examples
+///
+/// ```
+/// # use nix::sys::aio::*;
+/// # use nix::sys::signal::SigevNotify;
+/// # use nix::sys::time::{TimeSpec, TimeValLike};
+/// # use std::os::unix::io::AsRawFd;
+/// # use tempfile::tempfile;
+/// const WBUF: &[u8] = b"abcdef123456";
+/// let mut f = tempfile().unwrap();
+/// let mut aiocb = Box::pin(AioWrite::new(f.as_raw_fd(),
+///     2,   //offset
+///     WBUF,
+///     0,   //priority
+///     SigevNotify::SigevNone));
+/// aiocb.as_mut().submit().unwrap();
+/// aio_suspend_uninterrupted(&[&*aiocb], Some(TimeSpec::seconds(10)))
+///     .expect("aio_suspend failed");
+/// assert_eq!(aiocb.as_mut().aio_return().unwrap() as usize, WBUF.len());
+/// ```
+pub fn aio_suspend_uninterrupted(
+    list: &[&dyn AsRef<libc::aiocb>],
+    timeout: Option<TimeSpec>,
+) -> Result<()> {
+    let deadline = timeout.map(|t| Instant::now() + Duration::from(t));
+    let mut remaining = timeout;
+    loop {
+        match aio_suspend(list, remaining) {
+            Err(Errno::EINTR) => {
+                if let Some(deadline) = deadline {
+                    let left =
+                        deadline.saturating_duration_since(Instant::now());
+                    remaining = Some(TimeSpec::from(left));
+                }
+            }
+            r => return r,
+        }
+    }
+}
+
+/// Like [`aio_suspend`], but also report which operations have completed.
+///
+/// After waking, this checks the status of each operation in `list`, and
+/// returns the indices of those that are no longer in progress, in
+/// ascending order.  They are not reaped.
+///
+/// # Examples
+///
+/// ```
+/// # use nix::sys::aio::*;
+/// # use nix::sys::signal::SigevNotify;
+/// # use std::os::unix::io::AsRawFd;
+/// # use tempfile::tempfile;
+/// let f = tempfile().unwrap();
+/// let mut aiow = Box::pin(AioWrite::new(f.as_raw_fd(), 0, b"abcd", 0,
+///     SigevNotify::SigevNone));
+/// aiow.as_mut().submit().unwrap();
+/// assert_eq!(aio_suspend_any(&[&*aiow], None), Ok(vec![0]));
+/// aiow.as_mut().aio_return().unwrap();
+/// ```
+pub fn aio_suspend_any(
+    list: &[&dyn AsRef<libc::aiocb>],
+    timeout: Option<TimeSpec>,
+) -> Result<Vec<usize>> {
+    aio_suspend(list, timeout)?;
+    Ok(list
+        .iter()
+        .enumerate()
+        .filter(|(_, aiocb)| {
+            aio_error((**aiocb).as_ref()) != Err(Errno::EINPROGRESS)
+        })
+        .map(|(i, _)| i)
+        .collect())
+}
+
+/// The operation that [`aio_waitcomplete`] reaped.
+#[cfg(target_os = "freebsd")]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum AioCompletion {
+    /// The operation at this index of the list
+    Listed(usize),
+    /// An operation that wasn't in the list, identified by the address of its
+    /// `aiocb`.  It has been reaped, but its `Aio` object doesn't know that.
+    /// Calling [`Aio::aio_return`] on it will fail, but will record that it
+    /// is no longer in progress.
+    Unlisted(*mut libc::aiocb),
+}
+
+/// Waits for the next operation to complete, and reaps it.
+///
+/// Unlike [`aio_suspend`], this does not require polling each operation for
+/// its status afterwards.  It returns which operation completed, along with the
+/// value that [`Aio::aio_return`] would have returned for it.  The operation
+/// must not be reaped again.
+///
+/// `list` should contain every operation that the process has in flight.  If
+/// the one that completes is not in `list`, this returns the address of its
+/// `aiocb` instead of an index.  If `timeout` is `None`, it will block
+/// indefinitely.  Otherwise it fails with `EAGAIN` if no operation completes in
+/// time.
+///
+/// # Examples
+///
+/// ```
+/// # use nix::sys::aio::*;
+/// # use nix::sys::signal::SigevNotify;
+/// # use std::os::unix::io::AsRawFd;
+/// # use tempfile::tempfile;
+/// let f = tempfile().unwrap();
+/// let mut aiow = Box::pin(AioWrite::new(f.as_raw_fd(), 0, b"abcd", 0,
+///     SigevNotify::SigevNone));
+/// aiow.as_mut().submit().unwrap();
+/// let (op, r) = aio_waitcomplete(&mut [aiow.as_mut()], None).unwrap();
+/// assert_eq!(op, AioCompletion::Listed(0));
+/// assert_eq!(r, Ok(4));
+/// ```
+///
+/// # References
+///
+/// [aio_waitcomplete](https://www.freebsd.org/cgi/man.cgi?query=aio_waitcomplete)
+#[cfg(target_os = "freebsd")]
+pub fn aio_waitcomplete<T>(
+    list: &mut [Pin<&mut T>],
+    timeout: Option<TimeSpec>,
+) -> Result<(AioCompletion, Result<usize>)>
+where
+    T: Aio + AsRef<libc::aiocb> + ?Sized,
+{
+    let mut p = ptr::null_mut::<libc::aiocb>();
+    let mut timeout = timeout;
+    let timep = match timeout.as_mut() {
+        None => ptr::null_mut::<libc::timespec>(),
+        Some(x) => x.as_mut() as *mut libc::timespec,
+    };
+    let r = unsafe { libc::aio_waitcomplete(&mut p, timep) };
+    // If the operation itself failed, p is still set
+    if r == -1 && p.is_null() {
+        return Err(Errno::last());
+    }
+    let status = Errno::result(r).map(|r| r as usize);
+    let i = list.iter().position(|aiocb| {
+        ptr::eq(AsRef::<libc::aiocb>::as_ref(&**aiocb), p)
+    });
+    match i {
+        Some(i) => {
+            // The kernel has already forgotten the operation, so it mustn't
+            // be reaped again with aio_return.
+            private::AioPriv::set_reaped(list[i].as_mut());
+            Ok((AioCompletion::Listed(i), status))
+        }
+        None => Ok((AioCompletion::Unlisted(p), status)),
+    }
+}
+
 /// Submits multiple asynchronous I/O requests with a single system call.
 ///
 /// They are not guaranteed to complete atomically, and the order in which the
@@ -1132,6 +3462,11 @@ pub fn aio_suspend(
 /// `EINTR`, in which case some but not all operations may have been submitted.
 /// In that case, you must check the status of each individual operation, and
 /// possibly resubmit some.
+///
+/// Entries whose `aio_lio_opcode` is `LIO_NOP` are skipped by the operating
+/// system.  That makes it possible to submit a fixed-size array in which some
+/// slots are unused.  Such entries are never in progress and must not be
+/// reaped.
 /// ```
 /// # use libc::c_int;
 /// # use std::os::unix::io::AsRawFd;
@@ -1179,33 +3514,1171 @@ pub fn lio_listio(
     list: &mut [Pin<&mut dyn AsMut<libc::aiocb>>],
     sigev_notify: SigevNotify,
 ) -> Result<()> {
-    let p = list as *mut [Pin<&mut dyn AsMut<libc::aiocb>>]
-        as *mut [*mut libc::aiocb]
-        as *mut *mut libc::aiocb;
-    let sigev = SigEvent::new(sigev_notify);
-    let sigevp = &mut sigev.sigevent() as *mut libc::sigevent;
+    // The elements of list are fat pointers, so they must be converted to an
+    // array of thin pointers for libc.
+    let mut aiocbs = list
+        .iter_mut()
+        .map(|aiocb| {
+            // Safe because we never move the aiocb
+            unsafe { aiocb.as_mut().get_unchecked_mut() }.as_mut()
+                as *mut libc::aiocb
+        })
+        .collect::<Vec<_>>();
+    let p = aiocbs.as_mut_ptr();
+    // The sigevent is copied by lio_listio itself, so it need only live until
+    // the call returns.  But it must be a named local, not a temporary.
+    let mut sigev = SigEvent::new(sigev_notify);
+    let sigevp = sigev.as_mut_ptr();
     Errno::result(unsafe {
         libc::lio_listio(mode as i32, p, list.len() as i32, sigevp)
     })
     .map(drop)
 }
 
-#[cfg(test)]
-mod t {
-    use super::*;
-
-    /// aio_suspend relies on casting Rust Aio* struct pointers to libc::aiocb
-    /// pointers.  This test ensures that such casts are valid.
-    #[test]
-    fn casting() {
-        let sev = SigevNotify::SigevNone;
-        let aiof = AioFsync::new(666, AioFsyncMode::O_SYNC, 0, sev);
-        assert_eq!(
-            aiof.as_ref() as *const libc::aiocb,
-            &aiof as *const AioFsync as *const libc::aiocb
-        );
-
-        let mut rbuf = [];
+/// Guards against submitting overlapping, conflicting AIO operations.
+///
+/// Two in-flight operations on overlapping ranges of the same file, at least
+/// one of which is a write, complete in an unspecified order.  That's almost
+/// always a bug.  `AioTracker` records the file ranges of operations submitted
+/// through it, and refuses to submit a new operation that would conflict with
+/// an outstanding one.  Operations are forgotten when they are reaped with
+/// [`AioTracker::aio_return`], or when the [`AioTrackerGuard`] returned by
+/// [`AioTracker::submit`] is dropped, whichever comes first.
+///
+/// Operations that don't access a range of the file, like [`AioFsync`], are
+/// submitted without any checks.
+///
+/// # Examples
+///
+/// ```
+/// # use nix::errno::Errno;
+/// # use nix::sys::aio::*;
+/// # use nix::sys::signal::SigevNotify;
+/// # use std::os::unix::io::AsRawFd;
+/// # use tempfile::tempfile;
+/// let f = tempfile().unwrap();
+/// let mut tracker = AioTracker::default();
+/// let mut aiow0 = Box::pin(AioWrite::new(f.as_raw_fd(), 0, b"abcd", 0,
+///     SigevNotify::SigevNone));
+/// let mut aiow1 = Box::pin(AioWrite::new(f.as_raw_fd(), 2, b"efgh", 0,
+///     SigevNotify::SigevNone));
+/// let _guard = tracker.submit(aiow0.as_mut()).unwrap();
+/// assert_eq!(tracker.submit(aiow1.as_mut()).unwrap_err(), Errno::EBUSY);
+/// aio_suspend(&[&*aiow0], None).unwrap();
+/// tracker.aio_return(aiow0.as_mut()).unwrap();
+/// ```
+#[derive(Debug, Default)]
+pub struct AioTracker {
+    inflight: Arc<Mutex<Vec<AioTrackerEntry>>>,
+}
+
+#[derive(Debug)]
+struct AioTrackerEntry {
+    // The operation's Aio::id
+    id:       usize,
+    fd:       RawFd,
+    start:    off_t,
+    end:      off_t,
+    is_write: bool,
+}
+
+impl AioTracker {
+    fn entry<T>(aiocb: &T) -> Option<AioTrackerEntry>
+    where
+        T: Aio + AsRef<libc::aiocb>,
+    {
+        if !aiocb.is_read() && !aiocb.is_write() {
+            return None;
+        }
+        let a = aiocb.as_ref();
+        let len = match a.aio_lio_opcode {
+            // For vectored operations, aio_nbytes is the length of the iovec
+            #[cfg(target_os = "freebsd")]
+            libc::LIO_READV | libc::LIO_WRITEV => unsafe {
+                std::slice::from_raw_parts(
+                    a.aio_buf as *const libc::iovec,
+                    a.aio_nbytes,
+                )
+            }
+            .iter()
+            .map(|iov| iov.iov_len)
+            .sum(),
+            _ => a.aio_nbytes,
+        };
+        Some(AioTrackerEntry {
+            id:       aiocb.id(),
+            fd:       a.aio_fildes,
+            start:    a.aio_offset,
+            end:      a.aio_offset.saturating_add(len as off_t),
+            is_write: aiocb.is_write(),
+        })
+    }
+
+    /// Submit `aiocb`, unless it conflicts with an outstanding operation.
+    ///
+    /// Returns `EBUSY` if `aiocb` would access the same part of the same file
+    /// as an outstanding operation, and either of them is a write.  On
+    /// success, the operation's file range stays registered until it is
+    /// reaped with [`AioTracker::aio_return`] or the returned guard is
+    /// dropped.
+    pub fn submit<T>(&mut self, aiocb: Pin<&mut T>) -> Result<AioTrackerGuard>
+    where
+        T: Aio + AsRef<libc::aiocb>,
+    {
+        let guard = AioTrackerGuard {
+            inflight: Arc::downgrade(&self.inflight),
+            id:       aiocb.id(),
+        };
+        let entry = match Self::entry(&*aiocb) {
+            Some(entry) => entry,
+            None => return aiocb.submit().map(|_| guard),
+        };
+        let mut inflight = self.inflight.lock().unwrap();
+        let conflict = inflight.iter().any(|e| {
+            e.fd == entry.fd
+                && e.start < entry.end
+                && entry.start < e.end
+                && (e.is_write || entry.is_write)
+        });
+        if conflict {
+            return Err(Errno::EBUSY);
+        }
+        aiocb.submit()?;
+        inflight.push(entry);
+        Ok(guard)
+    }
+
+    /// Reap `aiocb` with [`Aio::aio_return`], and forget its file range.
+    pub fn aio_return<T>(&mut self, aiocb: Pin<&mut T>) -> Result<T::Output>
+    where
+        T: Aio + AsRef<libc::aiocb>,
+    {
+        let id = aiocb.id();
+        self.inflight.lock().unwrap().retain(|e| e.id != id);
+        aiocb.aio_return()
+    }
+}
+
+/// Keeps an operation's file range registered with an [`AioTracker`].
+///
+/// Returned by [`AioTracker::submit`].  Dropping it forgets the range, so
+/// keep it for as long as the operation is outstanding.  That way an
+/// operation that is reaped without [`AioTracker::aio_return`] can't block
+/// later submissions forever.
+#[derive(Debug)]
+#[must_use = "dropping the guard immediately forgets the operation's range"]
+pub struct AioTrackerGuard {
+    inflight: Weak<Mutex<Vec<AioTrackerEntry>>>,
+    id:       usize,
+}
+
+impl Drop for AioTrackerGuard {
+    fn drop(&mut self) {
+        if let Some(inflight) = self.inflight.upgrade() {
+            inflight.lock().unwrap().retain(|e| e.id != self.id);
+        }
+    }
+}
+
+/// An operation owned by an [`AioContext`]
+trait AioContextOp: Aio<Output = usize> + Debug {
+    fn as_aiocb(&self) -> &dyn AsRef<libc::aiocb>;
+}
+
+impl<T> AioContextOp for T
+where
+    T: Aio<Output = usize> + AsRef<libc::aiocb> + Debug,
+{
+    fn as_aiocb(&self) -> &dyn AsRef<libc::aiocb> {
+        self
+    }
+}
+
+/// Owns and tracks all outstanding AIO operations on one file descriptor.
+///
+/// Operations are submitted with [`AioContext::submit`], which returns a
+/// token identifying them.  Once complete, they are reaped with
+/// [`AioContext::drain`], which yields each one's token and result.  The
+/// context takes care of pairing every completion with exactly one call to
+/// `aio_return`.
+///
+/// Dropping an `AioContext` cancels any operations that are still in flight,
+/// blocks until they have all finished, and then reaps them.
+///
+/// # Examples
+///
+/// ```
+/// # use nix::sys::aio::*;
+/// # use nix::sys::signal::SigevNotify;
+/// # use std::os::unix::io::AsRawFd;
+/// # use tempfile::tempfile;
+/// let f = tempfile().unwrap();
+/// let mut ctx = AioContext::new(f.as_raw_fd());
+/// let t0 = ctx.submit(AioWrite::new(f.as_raw_fd(), 0, b"abcd", 0,
+///     SigevNotify::SigevNone)).unwrap();
+/// let t1 = ctx.submit(AioWrite::new(f.as_raw_fd(), 4, b"ef", 0,
+///     SigevNotify::SigevNone)).unwrap();
+/// ctx.suspend_all(None).unwrap();
+/// let mut results = ctx.drain().collect::<Vec<_>>();
+/// results.sort_by_key(|(t, _)| *t);
+/// assert_eq!(results, vec![(t0, Ok(4)), (t1, Ok(2))]);
+/// ```
+#[derive(Debug)]
+pub struct AioContext<'a> {
+    fd:         RawFd,
+    ops:        Vec<(usize, Pin<Box<dyn AioContextOp + 'a>>)>,
+    next_token: usize,
+}
+
+impl<'a> AioContext<'a> {
+    /// Create an empty context for operations on `fd`.
+    pub fn new(fd: RawFd) -> Self {
+        AioContext {
+            fd,
+            ops: Vec::new(),
+            next_token: 0,
+        }
+    }
+
+    /// Cancel every outstanding operation in the context, each with
+    /// [`Aio::cancel`].
+    ///
+    /// Unlike [`aio_cancel_all`], this leaves alone any other operations on
+    /// the same file descriptor.  The result summarizes all of the
+    /// cancellations, like `aio_cancel_all`'s would.  If any of them fails,
+    /// the rest are still attempted, and the first error is returned.
+    ///
+    /// The operations must still be reaped with [`AioContext::drain`].
+    /// Canceled ones report `ECANCELED`.
+    pub fn cancel_all(&mut self) -> Result<AioCancelStat> {
+        let mut stat = AioCancelStat::AioAllDone;
+        let mut error = None;
+        for (_, aiocb) in self.ops.iter_mut() {
+            match aiocb.as_mut().cancel() {
+                Ok(AioCancelStat::AioAllDone) => (),
+                Ok(AioCancelStat::AioCanceled) => {
+                    if stat == AioCancelStat::AioAllDone {
+                        stat = AioCancelStat::AioCanceled;
+                    }
+                }
+                Ok(AioCancelStat::AioNotCanceled) => {
+                    stat = AioCancelStat::AioNotCanceled;
+                }
+                Err(e) => {
+                    error.get_or_insert(e);
+                }
+            }
+        }
+        match error {
+            Some(e) => Err(e),
+            None => Ok(stat),
+        }
+    }
+
+    /// Reap every operation that has completed, and return their tokens and
+    /// results.
+    ///
+    /// Operations that are still in flight are left alone.
+    pub fn drain(&mut self) -> std::vec::IntoIter<(usize, Result<usize>)> {
+        let mut done = Vec::new();
+        let mut i = 0;
+        while i < self.ops.len() {
+            if self.ops[i].1.as_mut().state() == AioCbState::InFlight {
+                i += 1;
+            } else {
+                let (token, mut aiocb) = self.ops.swap_remove(i);
+                done.push((token, reap_completed(aiocb.as_mut())));
+            }
+        }
+        done.into_iter()
+    }
+
+    /// The file descriptor that this context manages.
+    pub fn fd(&self) -> RawFd {
+        self.fd
+    }
+
+    /// Are there no unreaped operations?
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    /// The number of operations that have been submitted but not yet reaped.
+    pub fn len(&self) -> usize {
+        self.ops.len()
+    }
+
+    /// Submit `aiocb`, taking ownership of it, and return its token.
+    ///
+    /// The operation must be on the context's file descriptor, or this will
+    /// fail with `EINVAL`.
+    pub fn submit<T>(&mut self, aiocb: T) -> Result<usize>
+    where
+        T: Aio<Output = usize> + AsRef<libc::aiocb> + Debug + 'a,
+    {
+        if aiocb.fd() != self.fd {
+            return Err(Errno::EINVAL);
+        }
+        let mut aiocb: Pin<Box<dyn AioContextOp + 'a>> = Box::pin(aiocb);
+        aiocb.as_mut().submit()?;
+        let token = self.next_token;
+        self.next_token += 1;
+        self.ops.push((token, aiocb));
+        Ok(token)
+    }
+
+    /// Wait until every outstanding operation has completed, or until
+    /// `timeout` elapses, in which case this fails with `EAGAIN`.
+    ///
+    /// If `timeout` is `None`, this will block indefinitely.  Signals do not
+    /// interrupt the wait.
+    pub fn suspend_all(&mut self, timeout: Option<TimeSpec>) -> Result<()> {
+        let deadline = timeout.map(|t| Instant::now() + Duration::from(t));
+        loop {
+            let inflight = self
+                .ops
+                .iter_mut()
+                .map(|(_, aiocb)| {
+                    aiocb.as_mut().state() == AioCbState::InFlight
+                })
+                .collect::<Vec<_>>();
+            let list = self
+                .ops
+                .iter()
+                .zip(inflight)
+                .filter(|(_, inflight)| *inflight)
+                .map(|((_, aiocb), _)| aiocb.as_aiocb())
+                .collect::<Vec<_>>();
+            if list.is_empty() {
+                return Ok(());
+            }
+            let remaining = deadline.map(|deadline| {
+                let left = deadline.saturating_duration_since(Instant::now());
+                TimeSpec::from(left)
+            });
+            aio_suspend_uninterrupted(&list, remaining)?;
+        }
+    }
+}
+
+impl<'a> Drop for AioContext<'a> {
+    fn drop(&mut self) {
+        if self.ops.is_empty() {
+            return;
+        }
+        let _ = self.cancel_all();
+        // The operations' buffers may only be freed once the kernel is done
+        // with them.  If aio_suspend fails, fall back to polling aio_error.
+        while self.ops.iter_mut().any(|(_, aiocb)| {
+            aiocb.as_mut().state() == AioCbState::InFlight
+        }) {
+            if self.suspend_all(None).is_err() {
+                thread::sleep(Duration::from_millis(1));
+            }
+        }
+        self.drain().for_each(drop);
+    }
+}
+
+/// Submit a write of `buf` at the current end of the file.
+///
+/// POSIX AIO has no equivalent of `O_APPEND`, so this function uses `fstat(2)`
+/// to find the file's size, and submits an [`AioWrite`] at that offset.
+///
+/// **This is not atomic.**  If another writer extends the file between the
+/// `fstat` and the write, or if a previous append is still in flight, the data
+/// will be written at the wrong place.  Only use it when there is a single
+/// writer that waits for each append to complete before starting the next.
+/// For a single writer with several appends in flight, see [`AioSeqWriter`].
+///
+/// # Examples
+///
+/// ```
+/// # use nix::sys::aio::*;
+/// # use nix::sys::signal::SigevNotify;
+/// # use std::io::Write;
+/// # use std::os::unix::io::AsRawFd;
+/// # use tempfile::tempfile;
+/// let mut f = tempfile().unwrap();
+/// f.write_all(b"abcd").unwrap();
+/// let mut aiow = aio_append(f.as_raw_fd(), b"efgh", 0,
+///     SigevNotify::SigevNone).unwrap();
+/// assert_eq!(aiow.offset(), 4);
+/// aio_suspend(&[&*aiow], None).unwrap();
+/// assert_eq!(aiow.as_mut().aio_return(), Ok(4));
+/// ```
+pub fn aio_append<'a>(
+    fd: RawFd,
+    buf: &'a [u8],
+    prio: i32,
+    sigev_notify: SigevNotify,
+) -> Result<Pin<Box<AioWrite<'a>>>> {
+    let mut st = mem::MaybeUninit::<libc::stat>::uninit();
+    Errno::result(unsafe { libc::fstat(fd, st.as_mut_ptr()) })?;
+    let size = unsafe { st.assume_init() }.st_size;
+    let mut aiow =
+        Box::pin(AioWrite::new(fd, size as off_t, buf, prio, sigev_notify));
+    aiow.as_mut().submit()?;
+    Ok(aiow)
+}
+
+/// Write `buf` to `fd` at offset `offs`, as a series of concurrent chunks.
+///
+/// `buf` is split into chunks of at most `chunk_size` bytes, and up to
+/// `max_inflight` of them are kept in flight at once.  Each chunk is reaped as
+/// it completes, and if it was only partially written, the remainder is
+/// resubmitted.  Returns the total number of bytes written, which on success
+/// is always `buf.len()`.
+///
+/// If any chunk fails, no further chunks are submitted.  The ones already in
+/// flight are waited for and reaped, and then the first error is returned.
+/// A chunk that makes no progress at all fails with `EIO`.
+///
+/// # Panics
+///
+/// If either `chunk_size` or `max_inflight` is zero.
+///
+/// # Examples
+///
+/// ```
+/// # use nix::sys::aio::*;
+/// # use std::io::Read;
+/// # use std::os::unix::io::AsRawFd;
+/// # use tempfile::tempfile;
+/// let mut f = tempfile().unwrap();
+/// let wbuf = vec![42u8; 10000];
+/// assert_eq!(aio_write_chunked(f.as_raw_fd(), 0, &wbuf, 4096, 2), Ok(10000));
+/// let mut rbuf = Vec::new();
+/// f.read_to_end(&mut rbuf).unwrap();
+/// assert_eq!(rbuf, wbuf);
+/// ```
+pub fn aio_write_chunked(
+    fd: RawFd,
+    offs: off_t,
+    buf: &[u8],
+    chunk_size: usize,
+    max_inflight: usize,
+) -> Result<usize> {
+    assert!(chunk_size > 0, "chunk_size must be nonzero");
+    assert!(max_inflight > 0, "max_inflight must be nonzero");
+    let mut pending = buf
+        .chunks(chunk_size)
+        .enumerate()
+        .map(|(i, chunk)| (offs + (i * chunk_size) as off_t, chunk))
+        .collect::<VecDeque<_>>();
+    let mut inflight = VecDeque::with_capacity(max_inflight);
+    let mut total = 0;
+    let mut status = Ok(());
+    loop {
+        while status.is_ok() && inflight.len() < max_inflight {
+            let (offset, chunk) = match pending.pop_front() {
+                Some(x) => x,
+                None => break,
+            };
+            let mut aiow = Box::pin(AioWrite::new(
+                fd,
+                offset,
+                chunk,
+                0,
+                SigevNotify::SigevNone,
+            ));
+            match aiow.as_mut().submit() {
+                Ok(()) => inflight.push_back((aiow, chunk)),
+                Err(e) => status = Err(e),
+            }
+        }
+        let (mut aiow, chunk) = match inflight.pop_front() {
+            Some(x) => x,
+            None => break,
+        };
+        match wait_and_reap(aiow.as_mut()) {
+            Ok(n) => {
+                total += n;
+                if n == 0 {
+                    status = status.and(Err(Errno::EIO));
+                } else if n < chunk.len() {
+                    let offset = aiow.offset() + n as off_t;
+                    pending.push_front((offset, &chunk[n..]));
+                }
+            }
+            Err(e) => status = status.and(Err(e)),
+        }
+    }
+    status.map(|_| total)
+}
+
+/// Submits [`AioRead`] operations at consecutive offsets of a file.
+///
+/// This is the reading counterpart of [`AioSeqWriter`].  Each call to
+/// [`AioSeqReader::read`] submits a read at the current offset, and then
+/// advances the offset past it.  Reap the reads with
+/// [`AioSeqReader::aio_return`], so that a short read at the end of the file
+/// leaves the offset at the true end of the data.
+///
+/// # Examples
+///
+/// ```
+/// # use nix::sys::aio::*;
+/// # use nix::sys::signal::SigevNotify;
+/// # use std::io::Write;
+/// # use std::os::unix::io::AsRawFd;
+/// # use tempfile::tempfile;
+/// let mut f = tempfile().unwrap();
+/// f.write_all(b"abcdef").unwrap();
+/// let mut reader = AioSeqReader::new(f.as_raw_fd(), 0);
+/// let mut rbuf = [0; 4];
+/// {
+///     let mut aior = reader.read(&mut rbuf, 0, SigevNotify::SigevNone)
+///         .unwrap();
+///     assert_eq!(reader.offset(), 4);
+///     aio_suspend(&[&*aior], None).unwrap();
+///     assert_eq!(reader.aio_return(aior.as_mut()), Ok(4));
+/// }
+/// assert_eq!(&rbuf, b"abcd");
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct AioSeqReader {
+    fd:     RawFd,
+    offset: off_t,
+}
+
+impl AioSeqReader {
+    /// Create a new `AioSeqReader` that will begin reading from `fd` at
+    /// `offset`.
+    pub fn new(fd: RawFd, offset: off_t) -> Self {
+        AioSeqReader { fd, offset }
+    }
+
+    /// Returns the file offset at which the next read will be submitted.
+    pub fn offset(&self) -> off_t {
+        self.offset
+    }
+
+    /// Submit a read into `buf` at the current offset, and advance the offset
+    /// by its length.
+    ///
+    /// If submission fails, the offset is left unchanged.
+    pub fn read<'a>(
+        &mut self,
+        buf: &'a mut [u8],
+        prio: i32,
+        sigev_notify: SigevNotify,
+    ) -> Result<Pin<Box<AioRead<'a>>>> {
+        let len = buf.len();
+        let mut aior = Box::pin(AioRead::new(
+            self.fd,
+            self.offset,
+            buf,
+            prio,
+            sigev_notify,
+        ));
+        aior.as_mut().submit()?;
+        self.offset += len as off_t;
+        Ok(aior)
+    }
+
+    /// Reap a read returned by [`AioSeqReader::read`].
+    ///
+    /// If the read was short, for example because it reached the end of the
+    /// file, the offset is moved back to the end of the data that was actually
+    /// read.  This only makes sense if reads are reaped in the order they were
+    /// submitted.
+    pub fn aio_return(&mut self, aior: Pin<&mut AioRead>) -> Result<usize> {
+        let start = aior.offset();
+        let requested = aior.nbytes();
+        let read = aior.aio_return()?;
+        if read < requested {
+            self.offset = start + read as off_t;
+        }
+        Ok(read)
+    }
+}
+
+/// Submits [`AioWrite`] operations at consecutive offsets of a file.
+///
+/// Each call to [`AioSeqWriter::write`] submits a write at the current
+/// offset, and then advances the offset past it, so a producer can stream
+/// data into a file without tracking offsets itself.  Reap the writes with
+/// [`AioSeqWriter::aio_return`] so that short writes can be accounted for.
+///
+/// # Examples
+///
+/// ```
+/// # use nix::sys::aio::*;
+/// # use nix::sys::signal::SigevNotify;
+/// # use std::os::unix::io::AsRawFd;
+/// # use tempfile::tempfile;
+/// let f = tempfile().unwrap();
+/// let mut writer = AioSeqWriter::new(f.as_raw_fd(), 0);
+/// let mut aiow = writer.write(b"abcd", 0, SigevNotify::SigevNone).unwrap();
+/// assert_eq!(writer.offset(), 4);
+/// aio_suspend(&[&*aiow], None).unwrap();
+/// assert_eq!(writer.aio_return(aiow.as_mut()), Ok(4));
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct AioSeqWriter {
+    fd:     RawFd,
+    offset: off_t,
+}
+
+impl AioSeqWriter {
+    /// Create a new `AioSeqWriter` that will begin writing to `fd` at
+    /// `offset`.
+    pub fn new(fd: RawFd, offset: off_t) -> Self {
+        AioSeqWriter { fd, offset }
+    }
+
+    /// Returns the file offset at which the next write will be submitted.
+    pub fn offset(&self) -> off_t {
+        self.offset
+    }
+
+    /// Submit a write of `buf` at the current offset, and advance the offset
+    /// by its length.
+    ///
+    /// If submission fails, the offset is left unchanged.
+    pub fn write<'a>(
+        &mut self,
+        buf: &'a [u8],
+        prio: i32,
+        sigev_notify: SigevNotify,
+    ) -> Result<Pin<Box<AioWrite<'a>>>> {
+        let mut aiow = Box::pin(AioWrite::new(
+            self.fd,
+            self.offset,
+            buf,
+            prio,
+            sigev_notify,
+        ));
+        aiow.as_mut().submit()?;
+        self.offset += buf.len() as off_t;
+        Ok(aiow)
+    }
+
+    /// Reap a write returned by [`AioSeqWriter::write`].
+    ///
+    /// If the write was short, the offset is moved back to the end of the data
+    /// that was actually written, so the next write will fill the gap.  This
+    /// only makes sense if writes are reaped in the order they were
+    /// submitted.
+    pub fn aio_return(&mut self, aiow: Pin<&mut AioWrite>) -> Result<usize> {
+        let start = aiow.offset();
+        let requested = aiow.nbytes();
+        let written = aiow.aio_return()?;
+        if written < requested {
+            self.offset = start + written as off_t;
+        }
+        Ok(written)
+    }
+}
+
+/// Error returned by [`lio_listio_partial`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct LioPartial {
+    /// The error returned by `lio_listio` itself.
+    pub errno:    Errno,
+    /// How many of the operations were accepted by the operating system.
+    ///
+    /// Accepted operations must still be reaped with `aio_return`.  The rest
+    /// may be resubmitted.  Use [`Aio::error`] to tell them apart: operations
+    /// that were rejected report `EAGAIN`, except on glibc, which may report
+    /// success or `EINVAL` for them instead.  On glibc, operations that
+    /// finished without transferring any data are not counted as accepted,
+    /// since submitting them again is harmless.
+    pub accepted: usize,
+}
+
+/// Like [`lio_listio`], but on failure report how many operations were
+/// accepted.
+///
+/// If `lio_listio` fails with `EAGAIN`, `EINTR`, or `EIO`, then some but not
+/// all operations may have been queued.  In that case, this function checks
+/// the status of each one and returns the number that were accepted, turning
+/// an ambiguous failure into a resumable one.  For any other error, no
+/// operations were queued.
+pub fn lio_listio_partial(
+    mode: LioMode,
+    list: &mut [Pin<&mut dyn AsMut<libc::aiocb>>],
+    sigev_notify: SigevNotify,
+) -> std::result::Result<(), LioPartial> {
+    let errno = match lio_listio(mode, list, sigev_notify) {
+        Ok(()) => return Ok(()),
+        Err(e) => e,
+    };
+    let mut accepted = 0;
+    if let Errno::EAGAIN | Errno::EINTR | Errno::EIO = errno {
+        for aiocb in list.iter_mut() {
+            // Safe because we never move the aiocb
+            let p = unsafe { aiocb.as_mut().get_unchecked_mut() }.as_mut();
+            if lio_accepted(p) {
+                accepted += 1;
+            }
+        }
+    }
+    Err(LioPartial { errno, accepted })
+}
+
+/// One operation owned by a [`LioCb`]
+#[derive(Debug)]
+enum LioEntry<'a> {
+    Read(Pin<Box<AioRead<'a>>>),
+    Write(Pin<Box<AioWrite<'a>>>),
+}
+
+impl<'a> LioEntry<'a> {
+    fn aiocb(&mut self) -> Pin<&mut AioCb> {
+        match self {
+            LioEntry::Read(aior) => aior.as_mut().aiocb(),
+            LioEntry::Write(aiow) => aiow.as_mut().aiocb(),
+        }
+    }
+
+    fn as_aiocb_mut(&mut self) -> Pin<&mut dyn AsMut<libc::aiocb>> {
+        match self {
+            LioEntry::Read(aior) => aior.as_mut(),
+            LioEntry::Write(aiow) => aiow.as_mut(),
+        }
+    }
+}
+
+/// A batch of AIO operations that owns its constituent operations, for
+/// submission with [`lio_listio`].
+///
+/// Unlike a slice of borrowed operations, a `LioCb` can be stored on the heap
+/// and kept alive across await points, or sent to another thread, while its
+/// operations are in flight.  Build one with [`LioCbBuilder`].
+///
+/// Every submitted operation must be reaped with [`LioCb::aio_return`] before
+/// the `LioCb` is dropped.
+///
+/// # Examples
+///
+/// ```
+/// # use nix::sys::aio::*;
+/// # use nix::sys::signal::SigevNotify;
+/// # use std::os::unix::io::AsRawFd;
+/// # use tempfile::tempfile;
+/// const WBUF: &[u8] = b"abcdef123456";
+/// let f = tempfile().unwrap();
+/// let mut rbuf = vec![0; 4];
+/// let mut liocb = LioCbBuilder::with_capacity(2)
+///     .emplace_write(f.as_raw_fd(), 2, WBUF, 0, SigevNotify::SigevNone)
+///     .emplace_read(f.as_raw_fd(), 8, &mut rbuf, 0, SigevNotify::SigevNone)
+///     .finish();
+/// liocb.listio(LioMode::LIO_WAIT, SigevNotify::SigevNone).unwrap();
+/// assert_eq!(liocb.aio_return(0), Ok(WBUF.len()));
+/// liocb.aio_return(1).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct LioCb<'a> {
+    entries: Vec<LioEntry<'a>>,
+}
+
+impl<'a> LioCb<'a> {
+    /// Retrieve the error status of the operation at `i`.
+    ///
+    /// See [`Aio::error`].
+    ///
+    /// # Panics
+    ///
+    /// If `i` is out of bounds.
+    pub fn error(&mut self, i: usize) -> Result<()> {
+        self.entries[i].aiocb().error()
+    }
+
+    /// Retrieve the return status of the operation at `i`, which must have
+    /// completed.
+    ///
+    /// See [`Aio::aio_return`].
+    ///
+    /// # Panics
+    ///
+    /// If `i` is out of bounds.
+    pub fn aio_return(&mut self, i: usize) -> Result<usize> {
+        self.entries[i].aiocb().aio_return()
+    }
+
+    /// Does this batch contain no operations?
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The number of operations in this batch.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Submit every operation in the batch with a single call to
+    /// [`lio_listio`].
+    ///
+    /// If `lio_listio` fails partway through, the operations that were
+    /// accepted are still considered in progress and must be reaped.  The
+    /// rest may be submitted again with [`LioCb::listio_resubmit`].  See also
+    /// [`lio_listio_partial`].
+    pub fn listio(
+        &mut self,
+        mode: LioMode,
+        sigev_notify: SigevNotify,
+    ) -> Result<()> {
+        let mask = vec![true; self.entries.len()];
+        self.listio_masked(&mask, mode, sigev_notify)
+    }
+
+    /// Resubmit the operations that the operating system rejected from a
+    /// previous call to [`LioCb::listio`] or `listio_resubmit`.
+    ///
+    /// When `lio_listio` fails with `EAGAIN`, `EINTR`, or `EIO`, some
+    /// operations may have been queued while others were rejected for lack
+    /// of resources.  This method submits again only the ones that were never
+    /// accepted.  Operations that were accepted but then failed or were
+    /// canceled are not retried.  Instead, their indices and errors are
+    /// returned, so the caller can decide what to do about them.  They must
+    /// still be reaped with [`LioCb::aio_return`].
+    ///
+    /// Like `listio`, this may fail partway through, in which case it may be
+    /// called again.
+    pub fn listio_resubmit(
+        &mut self,
+        mode: LioMode,
+        sigev_notify: SigevNotify,
+    ) -> Result<Vec<(usize, Errno)>> {
+        let mut failed = Vec::new();
+        let mut mask = Vec::with_capacity(self.entries.len());
+        for (i, entry) in self.entries.iter_mut().enumerate() {
+            let mut aiocb = entry.aiocb();
+            let state = aiocb.as_mut().state();
+            if matches!(state, AioCbState::Completed | AioCbState::Canceled) {
+                if let Err(e) = aiocb.error() {
+                    failed.push((i, e));
+                }
+            }
+            mask.push(state == AioCbState::NotSubmitted);
+        }
+        self.listio_masked(&mask, mode, sigev_notify)?;
+        Ok(failed)
+    }
+
+    /// Submit the operations selected by `mask` with `lio_listio`, and mark
+    /// the ones that were accepted as in progress.
+    fn listio_masked(
+        &mut self,
+        mask: &[bool],
+        mode: LioMode,
+        sigev_notify: SigevNotify,
+    ) -> Result<()> {
+        let mut list = self
+            .entries
+            .iter_mut()
+            .zip(mask.iter())
+            .filter(|(_, selected)| **selected)
+            .map(|(entry, _)| entry.as_aiocb_mut())
+            .collect::<Vec<_>>();
+        let r = lio_listio(mode, &mut list[..], sigev_notify);
+        drop(list);
+        let selected = self
+            .entries
+            .iter_mut()
+            .zip(mask.iter())
+            .filter(|(_, selected)| **selected);
+        for (entry, _) in selected {
+            let accepted = match r {
+                Ok(()) => true,
+                Err(Errno::EAGAIN | Errno::EINTR | Errno::EIO) => {
+                    lio_accepted(&mut entry.aiocb().aiocb().0)
+                }
+                Err(_) => false,
+            };
+            if accepted {
+                entry.aiocb().set_in_progress();
+            }
+        }
+        r
+    }
+}
+
+/// Builds a [`LioCb`].
+#[derive(Debug)]
+pub struct LioCbBuilder<'a> {
+    entries: Vec<LioEntry<'a>>,
+}
+
+impl<'a> LioCbBuilder<'a> {
+    /// Create an empty builder with room for `capacity` operations.
+    pub fn with_capacity(capacity: usize) -> Self {
+        LioCbBuilder {
+            entries: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Add a read to the batch.  The arguments are the same as for
+    /// [`AioRead::new`].
+    pub fn emplace_read(
+        mut self,
+        fd: RawFd,
+        offs: off_t,
+        buf: &'a mut [u8],
+        prio: i32,
+        sigev_notify: SigevNotify,
+    ) -> Self {
+        let aior = AioRead::new(fd, offs, buf, prio, sigev_notify);
+        self.entries.push(LioEntry::Read(Box::pin(aior)));
+        self
+    }
+
+    /// Add a write to the batch.  The arguments are the same as for
+    /// [`AioWrite::new`].
+    pub fn emplace_write(
+        mut self,
+        fd: RawFd,
+        offs: off_t,
+        buf: &'a [u8],
+        prio: i32,
+        sigev_notify: SigevNotify,
+    ) -> Self {
+        let aiow = AioWrite::new(fd, offs, buf, prio, sigev_notify);
+        self.entries.push(LioEntry::Write(Box::pin(aiow)));
+        self
+    }
+
+    /// Finish building the batch.
+    pub fn finish(self) -> LioCb<'a> {
+        LioCb {
+            entries: self.entries,
+        }
+    }
+}
+
+/// An operation that can be run by an [`AioExecutor`].
+///
+/// Unlike the native AIO types, these own their buffers, so they need not be
+/// pinned and may be freely moved after submission.
+#[derive(Debug)]
+pub enum AioExecutorOp {
+    /// Read `buf.len()` bytes from `fd` at offset `offs`, like `pread(2)`.
+    Read {
+        /// File descriptor to read from
+        fd:   RawFd,
+        /// File offset
+        offs: off_t,
+        /// Buffer to read into.  It will be returned by
+        /// [`AioExecutorHandle::reap`].
+        buf:  Vec<u8>,
+    },
+    /// Write all of `buf` to `fd` at offset `offs`, like `pwrite(2)`.
+    Write {
+        /// File descriptor to write to
+        fd:   RawFd,
+        /// File offset
+        offs: off_t,
+        /// Data to write.  It will be returned by
+        /// [`AioExecutorHandle::reap`].
+        buf:  Vec<u8>,
+    },
+    /// Synchronize `fd`, like `fsync(2)` or `fdatasync(2)`.
+    Fsync {
+        /// File descriptor to sync
+        fd:   RawFd,
+        /// Whether to sync file metadata too, or just data.
+        mode: AioFsyncMode,
+    },
+}
+
+impl AioExecutorOp {
+    /// Synchronously perform the operation, returning its result and buffer.
+    fn run(self) -> (Result<usize>, Vec<u8>) {
+        match self {
+            AioExecutorOp::Read { fd, offs, mut buf } => {
+                let r = unsafe {
+                    libc::pread(
+                        fd,
+                        buf.as_mut_ptr() as *mut c_void,
+                        buf.len(),
+                        offs,
+                    )
+                };
+                (Errno::result(r).map(|r| r as usize), buf)
+            }
+            AioExecutorOp::Write { fd, offs, buf } => {
+                let r = unsafe {
+                    libc::pwrite(
+                        fd,
+                        buf.as_ptr() as *const c_void,
+                        buf.len(),
+                        offs,
+                    )
+                };
+                (Errno::result(r).map(|r| r as usize), buf)
+            }
+            AioExecutorOp::Fsync { fd, mode } => {
+                let r = match mode {
+                    #[cfg(any(
+                        target_os = "linux",
+                        target_os = "netbsd",
+                        target_os = "openbsd"
+                    ))]
+                    AioFsyncMode::O_DSYNC => unsafe { libc::fdatasync(fd) },
+                    _ => unsafe { libc::fsync(fd) },
+                };
+                (Errno::result(r).map(|_| 0), Vec::new())
+            }
+        }
+    }
+}
+
+type AioExecutorResult = Option<(Result<usize>, Vec<u8>)>;
+
+/// Completion state shared between an [`AioExecutorHandle`] and the worker
+/// thread running its operation.
+#[derive(Debug, Default)]
+struct AioExecutorSlot {
+    result: Mutex<AioExecutorResult>,
+    cv:     Condvar,
+}
+
+/// A pure user-space substitute for POSIX AIO.
+///
+/// Some platforms' native AIO implementations are weak.  For example, glibc
+/// emulates POSIX AIO with its own thread pool, which limits concurrency and
+/// differs in its `fsync` semantics, and some minimal libcs lack it entirely.
+/// `AioExecutor` provides the same submit/poll/reap style of interface, but
+/// runs each operation synchronously with `pread(2)`, `pwrite(2)`, or
+/// `fsync(2)` on a private pool of threads.
+///
+/// Choosing between native AIO and `AioExecutor` is a runtime decision left to
+/// the caller: nothing in this module uses the executor implicitly.  Dropping
+/// the executor waits for all previously submitted operations to finish.
+///
+/// # Examples
+///
+/// ```
+/// # use nix::sys::aio::*;
+/// # use std::io::Write;
+/// # use std::os::unix::io::AsRawFd;
+/// # use tempfile::tempfile;
+/// let mut f = tempfile().unwrap();
+/// f.write_all(b"abcdef123456").unwrap();
+/// let executor = AioExecutor::new(2);
+/// let handle = executor.submit(AioExecutorOp::Read {
+///     fd: f.as_raw_fd(),
+///     offs: 2,
+///     buf: vec![0; 4]
+/// });
+/// let (r, buf) = handle.reap();
+/// assert_eq!(r, Ok(4));
+/// assert_eq!(buf, b"cdef");
+/// ```
+#[derive(Debug)]
+pub struct AioExecutor {
+    sender:  Option<mpsc::Sender<(AioExecutorOp, Arc<AioExecutorSlot>)>>,
+    workers: Vec<thread::JoinHandle<()>>,
+}
+
+impl AioExecutor {
+    /// Create a new executor backed by `nthreads` worker threads.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `nthreads` is zero.
+    pub fn new(nthreads: usize) -> Self {
+        assert!(nthreads > 0, "AioExecutor needs at least one thread");
+        let (sender, receiver) =
+            mpsc::channel::<(AioExecutorOp, Arc<AioExecutorSlot>)>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let workers = (0..nthreads)
+            .map(|_| {
+                let receiver = receiver.clone();
+                thread::spawn(move || loop {
+                    let job = receiver.lock().unwrap().recv();
+                    match job {
+                        Ok((op, slot)) => {
+                            let r = op.run();
+                            *slot.result.lock().unwrap() = Some(r);
+                            slot.cv.notify_all();
+                        }
+                        // The executor has been dropped
+                        Err(_) => break,
+                    }
+                })
+            })
+            .collect();
+        AioExecutor {
+            sender: Some(sender),
+            workers,
+        }
+    }
+
+    /// Queue an operation for execution on the thread pool.
+    pub fn submit(&self, op: AioExecutorOp) -> AioExecutorHandle {
+        let slot = Arc::new(AioExecutorSlot::default());
+        self.sender
+            .as_ref()
+            .unwrap()
+            .send((op, slot.clone()))
+            .expect("AioExecutor worker threads have exited");
+        AioExecutorHandle { slot }
+    }
+}
+
+impl Drop for AioExecutor {
+    fn drop(&mut self) {
+        // Closing the channel tells the workers to exit once it's drained.
+        drop(self.sender.take());
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// A handle to an operation submitted to an [`AioExecutor`].
+#[derive(Debug)]
+pub struct AioExecutorHandle {
+    slot: Arc<AioExecutorSlot>,
+}
+
+impl AioExecutorHandle {
+    /// Retrieve the error status of the operation.
+    ///
+    /// Like [`Aio::error`], returns `EINPROGRESS` if the operation has not yet
+    /// completed.
+    pub fn poll(&self) -> Result<()> {
+        match &*self.slot.result.lock().unwrap() {
+            None => Err(Errno::EINPROGRESS),
+            Some((r, _)) => r.map(drop),
+        }
+    }
+
+    /// Wait for the operation to complete, and return its result along with
+    /// its buffer.
+    ///
+    /// The result is the same as for the synchronous `pread(2)`, `pwrite(2)`,
+    /// or `fsync(2)` functions.  The buffer is empty for `Fsync` operations.
+    pub fn reap(self) -> (Result<usize>, Vec<u8>) {
+        let mut guard = self.slot.result.lock().unwrap();
+        loop {
+            if let Some(r) = guard.take() {
+                return r;
+            }
+            guard = self.slot.cv.wait(guard).unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+
+    /// aio_suspend relies on casting Rust Aio* struct pointers to libc::aiocb
+    /// pointers.  This test ensures that such casts are valid.
+    #[test]
+    fn casting() {
+        let sev = SigevNotify::SigevNone;
+        let aiof = AioFsync::new(666, AioFsyncMode::O_SYNC, 0, sev);
+        assert_eq!(
+            aiof.as_ref() as *const libc::aiocb,
+            &aiof as *const AioFsync as *const libc::aiocb
+        );
+
+        let mut rbuf = [];
         let aior = AioRead::new(666, 0, &mut rbuf, 0, sev);
         assert_eq!(
             aior.as_ref() as *const libc::aiocb,
@@ -1220,6 +4693,40 @@ mod t {
         );
     }
 
+    #[test]
+    fn is_read_is_write() {
+        let sev = SigevNotify::SigevNone;
+        let mut aiocb = AioCb::common_init(666, 0, sev);
+        aiocb.aiocb.0.aio_lio_opcode = libc::LIO_NOP;
+        assert!(!aiocb.is_read());
+        assert!(!aiocb.is_write());
+
+        let mut rbuf = [];
+        let aior = AioRead::new(666, 0, &mut rbuf, 0, sev);
+        assert!(aior.is_read());
+        assert!(!aior.is_write());
+
+        let wbuf = [];
+        let aiow = AioWrite::new(666, 0, &wbuf, 0, sev);
+        assert!(!aiow.is_read());
+        assert!(aiow.is_write());
+
+        let aiof = AioFsync::new(666, AioFsyncMode::O_SYNC, 0, sev);
+        assert!(!aiof.is_read());
+        assert!(!aiof.is_write());
+    }
+
+    /// Submitting an `AioCb` whose opcode is `LIO_NOP` should fail without
+    /// marking it as in-progress.
+    #[test]
+    fn submit_nop() {
+        let mut aiocb = AioCb::common_init(666, 0, SigevNotify::SigevNone);
+        aiocb.aiocb.0.aio_lio_opcode = libc::LIO_NOP;
+        let mut aiocb = Pin::new(&mut aiocb);
+        assert_eq!(Err(Errno::EINVAL), aiocb.as_mut().submit());
+        assert!(!aiocb.in_progress());
+    }
+
     #[cfg(target_os = "freebsd")]
     #[test]
     fn casting_vectored() {
@@ -1017,6 +1017,40 @@ pub enum SigevNotify {
         si_value: libc::intptr_t
     },
 }
+
+/// The kind of notification described by a [`SigevNotify`], without its
+/// payload.
+#[cfg(not(any(target_os = "openbsd", target_os = "redox")))]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SigevNotifyKind {
+    /// See [`SigevNotify::SigevNone`]
+    SigevNone,
+    /// See [`SigevNotify::SigevSignal`]
+    SigevSignal,
+    /// See [`SigevNotify::SigevKevent`]
+    #[cfg(any(target_os = "dragonfly", target_os = "freebsd"))]
+    #[cfg_attr(docsrs, doc(cfg(all())))]
+    SigevKevent,
+    /// See [`SigevNotify::SigevThreadId`]
+    #[cfg(any(target_os = "freebsd", target_os = "linux"))]
+    #[cfg_attr(docsrs, doc(cfg(all())))]
+    SigevThreadId,
+}
+
+#[cfg(not(any(target_os = "openbsd", target_os = "redox")))]
+impl SigevNotify {
+    /// Returns the kind of notification, without its payload.
+    pub fn kind(&self) -> SigevNotifyKind {
+        match self {
+            SigevNotify::SigevNone => SigevNotifyKind::SigevNone,
+            SigevNotify::SigevSignal{..} => SigevNotifyKind::SigevSignal,
+            #[cfg(any(target_os = "dragonfly", target_os = "freebsd"))]
+            SigevNotify::SigevKevent{..} => SigevNotifyKind::SigevKevent,
+            #[cfg(any(target_os = "freebsd", target_os = "linux"))]
+            SigevNotify::SigevThreadId{..} => SigevNotifyKind::SigevThreadId,
+        }
+    }
+}
 }
 
 #[cfg(not(any(target_os = "openbsd", target_os = "redox")))]
@@ -1027,7 +1061,7 @@ mod sigevent {
 
     use std::mem;
     use std::ptr;
-    use super::SigevNotify;
+    use super::{SigevNotify, SigevNotifyKind};
     #[cfg(any(target_os = "freebsd", target_os = "linux"))]
     use super::type_of_thread_id;
 
@@ -1113,6 +1147,23 @@ mod sigevent {
         pub fn as_mut_ptr(&mut self) -> *mut libc::sigevent {
             &mut self.sigevent
         }
+
+        /// Returns the kind of notification that will be delivered, or `None`
+        /// if it isn't one that [`SigevNotify`] can describe.
+        pub fn notify_kind(&self) -> Option<SigevNotifyKind> {
+            match self.sigevent.sigev_notify {
+                libc::SIGEV_NONE => Some(SigevNotifyKind::SigevNone),
+                libc::SIGEV_SIGNAL => Some(SigevNotifyKind::SigevSignal),
+                #[cfg(any(target_os = "dragonfly", target_os = "freebsd"))]
+                libc::SIGEV_KEVENT => Some(SigevNotifyKind::SigevKevent),
+                #[cfg(target_os = "freebsd")]
+                libc::SIGEV_THREAD_ID => Some(SigevNotifyKind::SigevThreadId),
+                // Not every Linux libc defines SIGEV_THREAD_ID
+                #[cfg(target_os = "linux")]
+                4 => Some(SigevNotifyKind::SigevThreadId),
+                _ => None
+            }
+        }
     }
 
     impl<'a> From<&'a libc::sigevent> for SigEvent {
@@ -1329,6 +1380,30 @@ mod tests {
         .unwrap();
     }
 
+    #[test]
+    #[cfg(not(target_os = "redox"))]
+    fn test_sigev_notify_kind() {
+        let sevs = [
+            SigevNotify::SigevNone,
+            SigevNotify::SigevSignal { signal: SIGUSR1, si_value: 0 },
+            #[cfg(any(target_os = "dragonfly", target_os = "freebsd"))]
+            SigevNotify::SigevKevent { kq: 0, udata: 0 },
+            #[cfg(any(target_os = "freebsd", target_os = "linux"))]
+            SigevNotify::SigevThreadId {
+                signal: SIGUSR1,
+                thread_id: 0,
+                si_value: 0
+            },
+        ];
+        for sev in sevs.iter() {
+            assert_eq!(Some(sev.kind()), SigEvent::new(*sev).notify_kind());
+        }
+        assert_eq!(
+            SigevNotifyKind::SigevSignal,
+            SigevNotify::SigevSignal { signal: SIGUSR1, si_value: 0 }.kind()
+        );
+    }
+
     #[test]
     fn test_from_sigset_t_unchecked() {
         let src_set = SigSet::empty();
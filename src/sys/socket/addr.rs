@@ -4,7 +4,7 @@ use crate::{Result, NixPath};
 use crate::errno::Errno;
 use memoffset::offset_of;
 use std::{fmt, mem, net, ptr, slice};
-use std::convert::TryInto;
+use std::convert::{TryFrom, TryInto};
 use std::ffi::OsStr;
 use std::hash::{Hash, Hasher};
 use std::path::Path;
@@ -74,6 +74,16 @@ pub enum AddressFamily {
     #[cfg(any(target_os = "android", target_os = "linux"))]
     #[cfg_attr(docsrs, doc(cfg(all())))]
     Netlink = libc::AF_NETLINK,
+    /// Routing socket, for monitoring and changing the routing table (see
+    /// [`route(4)`](https://www.freebsd.org/cgi/man.cgi?query=route&sektion=4))
+    #[cfg(any(target_os = "dragonfly",
+              target_os = "freebsd",
+              target_os = "ios",
+              target_os = "macos",
+              target_os = "netbsd",
+              target_os = "openbsd"))]
+    #[cfg_attr(docsrs, doc(cfg(all())))]
+    Route = libc::AF_ROUTE,
     /// Low level packet interface (see [`packet(7)`](https://man7.org/linux/man-pages/man7/packet.7.html))
     #[cfg(any(target_os = "android",
               target_os = "linux",
@@ -829,6 +839,37 @@ impl UnixAddr {
         }
     }
 
+    /// Create a new `sockaddr_un` representing an "unnamed" unix socket
+    /// address.
+    ///
+    /// Binding a socket to this address makes Linux "autobind" it to a unique
+    /// name in the abstract namespace, which can be read back with
+    /// [`getsockname`](crate::sys::socket::getsockname).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use nix::sys::socket::*;
+    /// let fd = socket(AddressFamily::Unix, SockType::SeqPacket,
+    ///     SockFlag::empty(), None).unwrap();
+    /// bind(fd, &UnixAddr::new_unnamed()).unwrap();
+    /// let addr: UnixAddr = getsockname(fd).unwrap();
+    /// assert!(addr.as_abstract().is_some());
+    /// ```
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[cfg_attr(docsrs, doc(cfg(all())))]
+    pub fn new_unnamed() -> UnixAddr {
+        let ret = libc::sockaddr_un {
+            sun_family: AddressFamily::Unix as sa_family_t,
+            .. unsafe { mem::zeroed() }
+        };
+
+        let sun_len: u8 = offset_of!(libc::sockaddr_un, sun_path).try_into()
+            .unwrap();
+
+        unsafe { UnixAddr::from_raw_parts(ret, sun_len) }
+    }
+
     /// Create a UnixAddr from a raw `sockaddr_un` struct and a size. `sun_len`
     /// is the size of the valid portion of the struct, excluding any trailing
     /// NUL.
@@ -882,6 +923,12 @@ impl UnixAddr {
         }
     }
 
+    /// Check if this address is an "unnamed" unix socket address.
+    #[inline]
+    pub fn is_unnamed(&self) -> bool {
+        matches!(self.kind(), UnixAddrKind::Unnamed)
+    }
+
     /// Returns the addrlen of this socket - `offsetof(struct sockaddr_un, sun_path)`
     #[inline]
     pub fn path_len(&self) -> usize {
@@ -1247,6 +1294,26 @@ impl From<SockaddrIn> for net::SocketAddrV4 {
     }
 }
 
+#[cfg(feature = "net")]
+impl From<SockaddrIn> for net::SocketAddr {
+    fn from(addr: SockaddrIn) -> Self {
+        net::SocketAddr::V4(addr.into())
+    }
+}
+
+#[cfg(feature = "net")]
+impl TryFrom<net::SocketAddr> for SockaddrIn {
+    type Error = Errno;
+
+    /// Fails with `EAFNOSUPPORT` for an IPv6 address.
+    fn try_from(addr: net::SocketAddr) -> Result<Self> {
+        match addr {
+            net::SocketAddr::V4(sa4) => Ok(Self::from(sa4)),
+            net::SocketAddr::V6(_) => Err(Errno::EAFNOSUPPORT),
+        }
+    }
+}
+
 #[cfg(feature = "net")]
 impl std::str::FromStr for SockaddrIn {
     type Err = net::AddrParseError;
@@ -1349,12 +1416,32 @@ impl From<SockaddrIn6> for net::SocketAddrV6 {
         net::SocketAddrV6::new(
             net::Ipv6Addr::from(addr.0.sin6_addr.s6_addr),
             u16::from_be(addr.0.sin6_port),
-            u32::from_be(addr.0.sin6_flowinfo),
-            u32::from_be(addr.0.sin6_scope_id)
+            addr.0.sin6_flowinfo,
+            addr.0.sin6_scope_id
         )
     }
 }
 
+#[cfg(feature = "net")]
+impl From<SockaddrIn6> for net::SocketAddr {
+    fn from(addr: SockaddrIn6) -> Self {
+        net::SocketAddr::V6(addr.into())
+    }
+}
+
+#[cfg(feature = "net")]
+impl TryFrom<net::SocketAddr> for SockaddrIn6 {
+    type Error = Errno;
+
+    /// Fails with `EAFNOSUPPORT` for an IPv4 address.
+    fn try_from(addr: net::SocketAddr) -> Result<Self> {
+        match addr {
+            net::SocketAddr::V6(sa6) => Ok(Self::from(sa6)),
+            net::SocketAddr::V4(_) => Err(Errno::EAFNOSUPPORT),
+        }
+    }
+}
+
 #[cfg(feature = "net")]
 impl std::str::FromStr for SockaddrIn6 {
     type Err = net::AddrParseError;
@@ -1640,6 +1727,22 @@ impl From<net::SocketAddr> for SockaddrStorage {
     }
 }
 
+#[cfg(feature = "net")]
+impl TryFrom<SockaddrStorage> for net::SocketAddr {
+    type Error = Errno;
+
+    /// Fails with `EAFNOSUPPORT` unless the address is an IPv4 or IPv6 one.
+    fn try_from(addr: SockaddrStorage) -> Result<Self> {
+        if let Some(sin) = addr.as_sockaddr_in() {
+            Ok(net::SocketAddr::from(*sin))
+        } else if let Some(sin6) = addr.as_sockaddr_in6() {
+            Ok(net::SocketAddr::from(*sin6))
+        } else {
+            Err(Errno::EAFNOSUPPORT)
+        }
+    }
+}
+
 impl Hash for SockaddrStorage {
     fn hash<H: Hasher>(&self, s: &mut H) {
         unsafe {
@@ -2329,7 +2432,7 @@ pub mod sys_control {
 mod datalink {
     feature! {
     #![feature = "net"]
-    use super::{fmt, mem, private, ptr, SockaddrLike};
+    use super::{fmt, mem, offset_of, private, ptr, SockaddrLike};
 
     /// Hardware Address
     #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
@@ -2337,6 +2440,26 @@ mod datalink {
     pub struct LinkAddr(pub(in super::super) libc::sockaddr_ll);
 
     impl LinkAddr {
+        /// Construct a new address for binding or sending on an `AF_PACKET`
+        /// socket.
+        ///
+        /// `protocol` is an `ETH_P_*` value in network byte order, like the
+        /// value returned by [`LinkAddr::protocol`].  `addr`, if given, is the
+        /// destination's MAC address.
+        pub fn new(protocol: u16, ifindex: usize, addr: Option<[u8; 6]>)
+            -> LinkAddr
+        {
+            let mut sll: libc::sockaddr_ll = unsafe { mem::zeroed() };
+            sll.sll_family = libc::AF_PACKET as libc::sa_family_t;
+            sll.sll_protocol = protocol;
+            sll.sll_ifindex = ifindex as libc::c_int;
+            if let Some(addr) = addr {
+                sll.sll_halen = addr.len() as u8;
+                sll.sll_addr[..addr.len()].copy_from_slice(&addr);
+            }
+            LinkAddr(sll)
+        }
+
         /// Physical-layer protocol
         pub fn protocol(&self) -> u16 {
             self.0.sll_protocol
@@ -2397,15 +2520,22 @@ mod datalink {
                            len: Option<libc::socklen_t>)
             -> Option<Self> where Self: Sized
         {
-            if let Some(l) = len {
-                if l != mem::size_of::<libc::sockaddr_ll>() as libc::socklen_t {
-                    return None;
-                }
+            // The kernel reports only as much of sll_addr as the hardware
+            // address needs, so the length may be shorter than the struct.
+            let min = offset_of!(libc::sockaddr_ll, sll_addr);
+            let max = mem::size_of::<libc::sockaddr_ll>();
+            let l = len.map_or(max, |l| l as usize);
+            if l < min || l > max {
+                return None;
             }
             if (*addr).sa_family as i32 != libc::AF_PACKET as i32 {
                 return None;
             }
-            Some(Self(ptr::read_unaligned(addr as *const _)))
+            let mut sll: libc::sockaddr_ll = mem::zeroed();
+            ptr::copy_nonoverlapping(addr as *const u8,
+                                     &mut sll as *mut _ as *mut u8,
+                                     l);
+            Some(Self(sll))
         }
     }
 
@@ -77,6 +77,15 @@ pub use crate::sys::socket::addr::netlink::NetlinkAddr;
 pub use crate::sys::socket::addr::alg::AlgAddr;
 #[cfg(any(target_os = "android", target_os = "linux"))]
 pub use crate::sys::socket::addr::vsock::VsockAddr;
+// Well-known context identifiers and ports for VsockAddr
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub use libc::{
+    VMADDR_CID_ANY,
+    VMADDR_CID_HOST,
+    VMADDR_CID_HYPERVISOR,
+    VMADDR_CID_LOCAL,
+    VMADDR_PORT_ANY,
+};
 
 #[cfg(feature = "uio")]
 pub use libc::{cmsghdr, msghdr};
@@ -132,6 +141,14 @@ pub enum SockProtocol {
     Tcp = libc::IPPROTO_TCP,
     /// UDP protocol ([ip(7)](https://man7.org/linux/man-pages/man7/ip.7.html))
     Udp = libc::IPPROTO_UDP,
+    /// ICMP protocol ([icmp(7)](https://man7.org/linux/man-pages/man7/icmp.7.html))
+    // On Apple platforms this collides with KextEvent, so it is an associated
+    // constant there instead.
+    #[cfg(not(any(target_os = "ios", target_os = "macos")))]
+    #[cfg_attr(docsrs, doc(cfg(all())))]
+    Icmp = libc::IPPROTO_ICMP,
+    /// ICMPv6 protocol ([icmp6(4)](https://www.freebsd.org/cgi/man.cgi?query=icmp6&sektion=4))
+    IcmpV6 = libc::IPPROTO_ICMPV6,
     /// Allows applications and other KEXTs to be notified when certain kernel events occur
     /// ([ref](https://developer.apple.com/library/content/documentation/Darwin/Conceptual/NKEConceptual/control/control.html))
     #[cfg(any(target_os = "ios", target_os = "macos"))]
@@ -208,6 +225,11 @@ pub enum SockProtocol {
     #[cfg(any(target_os = "android", target_os = "linux"))]
     #[cfg_attr(docsrs, doc(cfg(all())))]
     NetlinkKObjectUEvent = libc::NETLINK_KOBJECT_UEVENT,
+    /// Generic netlink, which multiplexes families registered at runtime
+    /// ([ref](https://www.man7.org/linux/man-pages/man7/netlink.7.html))
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[cfg_attr(docsrs, doc(cfg(all())))]
+    NetlinkGeneric = libc::NETLINK_GENERIC,
     /// Netlink interface to request information about ciphers registered with the kernel crypto API as well as allow
     /// configuration of the kernel crypto API.
     /// ([ref](https://www.man7.org/linux/man-pages/man7/netlink.7.html))
@@ -223,6 +245,15 @@ pub enum SockProtocol {
     EthAll = libc::ETH_P_ALL.to_be(),
 }
 
+#[cfg(any(target_os = "ios", target_os = "macos"))]
+impl SockProtocol {
+    /// ICMP protocol ([icmp(4)](https://www.freebsd.org/cgi/man.cgi?query=icmp&sektion=4))
+    // Has the same value as libc::SYSPROTO_EVENT
+    #[allow(non_upper_case_globals)]
+    #[cfg_attr(docsrs, doc(cfg(all())))]
+    pub const Icmp: SockProtocol = SockProtocol::KextEvent;
+}
+
 #[cfg(any(target_os = "linux"))]
 libc_bitflags! {
     /// Configuration flags for `SO_TIMESTAMPING` interface
@@ -326,6 +357,15 @@ libc_bitflags!{
         #[cfg(any(target_os = "android", target_os = "linux"))]
         #[cfg_attr(docsrs, doc(cfg(all())))]
         MSG_ERRQUEUE;
+        /// Send the data without copying it into the kernel, on a socket that
+        /// has [`ZeroCopy`](sockopt::ZeroCopy) enabled.  The buffer must not
+        /// be modified until a completion for the send has been read with
+        /// [`recv_errqueue`].
+        ///
+        /// See [msg_zerocopy](https://www.kernel.org/doc/html/latest/networking/msg_zerocopy.html).
+        #[cfg(target_os = "linux")]
+        #[cfg_attr(docsrs, doc(cfg(all())))]
+        MSG_ZEROCOPY;
         /// Set the `close-on-exec` flag for the file descriptor received via a UNIX domain
         /// file descriptor using the `SCM_RIGHTS` operation (described in
         /// [unix(7)](https://linux.die.net/man/7/unix)).
@@ -494,13 +534,410 @@ cfg_if!{
     }
 }
 
+/// Credentials of the peer of a connected Unix domain socket.
+///
+/// Return type of [`peer_credentials`].
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+#[cfg_attr(docsrs, doc(cfg(all())))]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct PeerCred {
+    uid: libc::uid_t,
+    gid: libc::gid_t,
+    pid: Option<libc::pid_t>,
+}
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+impl PeerCred {
+    /// Returns the effective user identifier of the peer
+    pub fn uid(&self) -> libc::uid_t {
+        self.uid
+    }
+
+    /// Returns the effective group identifier of the peer
+    pub fn gid(&self) -> libc::gid_t {
+        self.gid
+    }
+
+    /// Returns the process identifier of the peer, on operating systems that
+    /// report it
+    pub fn pid(&self) -> Option<libc::pid_t> {
+        self.pid
+    }
+}
+
 feature! {
 #![feature = "net"]
+cfg_if! {
+    if #[cfg(target_os = "linux")] {
+        /// Statistics about a TCP connection.
+        ///
+        /// Return type of
+        /// [`TcpInfoOpt`](crate::sys::socket::sockopt::TcpInfoOpt).
+        /// Fields without an accessor are available through `AsRef`.
+        #[repr(transparent)]
+        #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+        pub struct TcpInfo(libc::tcp_info);
+
+        impl TcpInfo {
+            /// The connection's state, as one of the kernel's `TCP_*` values,
+            /// such as `TCP_ESTABLISHED`.
+            pub fn state(&self) -> u8 {
+                self.0.tcpi_state
+            }
+
+            /// Smoothed round trip time
+            pub fn rtt(&self) -> std::time::Duration {
+                std::time::Duration::from_micros(self.0.tcpi_rtt.into())
+            }
+
+            /// Round trip time variance
+            pub fn rtt_var(&self) -> std::time::Duration {
+                std::time::Duration::from_micros(self.0.tcpi_rttvar.into())
+            }
+
+            /// Congestion window, in segments
+            pub fn snd_cwnd(&self) -> u32 {
+                self.0.tcpi_snd_cwnd
+            }
+
+            /// Number of consecutive retransmission timeouts without an
+            /// acknowledgement
+            pub fn retransmits(&self) -> u8 {
+                self.0.tcpi_retransmits
+            }
+
+            /// Total number of segments retransmitted over the connection's
+            /// lifetime
+            pub fn total_retrans(&self) -> u32 {
+                self.0.tcpi_total_retrans
+            }
+        }
+
+        impl AsRef<libc::tcp_info> for TcpInfo {
+            fn as_ref(&self) -> &libc::tcp_info {
+                &self.0
+            }
+        }
+    } else if #[cfg(any(target_os = "ios", target_os = "macos"))] {
+        /// Statistics about a TCP connection.
+        ///
+        /// Return type of
+        /// [`TcpConnectionInfo`](crate::sys::socket::sockopt::TcpConnectionInfo).
+        /// Fields without an accessor are available through `AsRef`.
+        #[repr(transparent)]
+        #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+        pub struct TcpInfo(libc::tcp_connection_info);
+
+        impl TcpInfo {
+            /// The connection's state, as one of the kernel's `TCPS_*`
+            /// values, such as `TCPS_ESTABLISHED`.
+            pub fn state(&self) -> u8 {
+                self.0.tcpi_state
+            }
+
+            /// Smoothed round trip time
+            pub fn rtt(&self) -> std::time::Duration {
+                std::time::Duration::from_millis(self.0.tcpi_srtt.into())
+            }
+
+            /// Round trip time variance
+            pub fn rtt_var(&self) -> std::time::Duration {
+                std::time::Duration::from_millis(self.0.tcpi_rttvar.into())
+            }
+
+            /// Congestion window, in bytes
+            pub fn snd_cwnd(&self) -> u32 {
+                self.0.tcpi_snd_cwnd
+            }
+
+            /// Total number of bytes retransmitted over the connection's
+            /// lifetime
+            pub fn txretransmitbytes(&self) -> u64 {
+                self.0.tcpi_txretransmitbytes
+            }
+        }
+
+        impl AsRef<libc::tcp_connection_info> for TcpInfo {
+            fn as_ref(&self) -> &libc::tcp_connection_info {
+                &self.0
+            }
+        }
+    }
+}
+}
+
+/// A classic BPF program, for the
+/// [`AttachFilter`](sockopt::AttachFilter) and
+/// [`AttachReusePortCbpf`](sockopt::AttachReusePortCbpf) socket options.
+///
+/// See [filter](https://www.kernel.org/doc/html/latest/networking/filter.html)
+/// for the instruction set.
+///
+/// # Examples
+///
+/// ```
+/// # use nix::sys::socket::*;
+/// let (a, _b) = socketpair(AddressFamily::Unix, SockType::Datagram, None,
+///                          SockFlag::empty()).unwrap();
+/// // Discard everything sent to `a`
+/// setsockopt(a, sockopt::AttachFilter, &BpfProgram::drop_all()).unwrap();
+/// ```
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[cfg_attr(docsrs, doc(cfg(all())))]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct BpfProgram(Vec<libc::sock_filter>);
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+impl BpfProgram {
+    // Opcodes and offsets from linux/filter.h
+    const BPF_LD_W_ABS: u16 = 0x20;
+    const BPF_RET_A: u16 = 0x16;
+    const BPF_RET_K: u16 = 0x06;
+    const SKF_AD_CPU: u32 = (-0x1000i32 + 36) as u32;
+
+    fn stmt(code: u16, k: u32) -> libc::sock_filter {
+        libc::sock_filter { code, jt: 0, jf: 0, k }
+    }
+
+    /// Create a program from its instructions.
+    ///
+    /// Fails with `EINVAL` if there are more instructions than a
+    /// `sock_fprog` can describe.
+    pub fn new(instructions: &[libc::sock_filter]) -> Result<Self> {
+        use std::convert::TryFrom;
+
+        if libc::c_ushort::try_from(instructions.len()).is_err() {
+            return Err(Errno::EINVAL);
+        }
+        Ok(BpfProgram(instructions.to_vec()))
+    }
+
+    /// A filter that accepts every packet in full.
+    pub fn accept_all() -> Self {
+        BpfProgram(vec![Self::stmt(Self::BPF_RET_K, u32::MAX)])
+    }
+
+    /// A filter that drops every packet.
+    pub fn drop_all() -> Self {
+        BpfProgram(vec![Self::stmt(Self::BPF_RET_K, 0)])
+    }
+
+    /// A `SO_REUSEPORT` program that steers each packet to the socket whose
+    /// index in the group matches the CPU that received the packet.
+    pub fn reuseport_by_cpu() -> Self {
+        BpfProgram(vec![
+            Self::stmt(Self::BPF_LD_W_ABS, Self::SKF_AD_CPU),
+            Self::stmt(Self::BPF_RET_A, 0),
+        ])
+    }
+
+    /// The instructions of the program.
+    pub fn instructions(&self) -> &[libc::sock_filter] {
+        &self.0
+    }
+}
+
+/// An accept filter, for the [`AcceptFilter`](sockopt::AcceptFilter) socket
+/// option.
+///
+/// This has the layout of `struct accept_filter_arg`.  See
+/// [accept_filter(9)](https://www.freebsd.org/cgi/man.cgi?query=accept_filter&sektion=9)
+/// for the available filters.  Each is named after the kernel module that
+/// provides it, which must be loaded before use: `dataready` is provided by
+/// `accf_data`, `httpready` by `accf_http` and `tlsready` by `accf_tls`.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use nix::sys::socket::*;
+/// # let fd = 0;
+/// // Don't return connections from accept until they have data to read
+/// let filter = AcceptFilterArg::new("dataready", "").unwrap();
+/// setsockopt(fd, sockopt::AcceptFilter, &filter).unwrap();
+/// ```
+#[cfg(target_os = "freebsd")]
+#[cfg_attr(docsrs, doc(cfg(all())))]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct AcceptFilterArg {
+    af_name: [libc::c_char; 16],
+    af_arg: [libc::c_char; 256 - 16],
+}
+
+#[cfg(target_os = "freebsd")]
+impl AcceptFilterArg {
+    /// Select the filter `name`, with an argument that is specific to the
+    /// filter, and usually empty.
+    ///
+    /// Fails with `ENAMETOOLONG` if either string does not fit, or `EINVAL`
+    /// if either contains a NUL byte.
+    pub fn new(name: &str, arg: &str) -> Result<Self> {
+        fn copy(dst: &mut [libc::c_char], src: &str) -> Result<()> {
+            // Leave room for the terminating NUL
+            if src.len() >= dst.len() {
+                return Err(Errno::ENAMETOOLONG);
+            }
+            if src.bytes().any(|b| b == 0) {
+                return Err(Errno::EINVAL);
+            }
+            for (d, s) in dst.iter_mut().zip(src.bytes()) {
+                *d = s as libc::c_char;
+            }
+            Ok(())
+        }
+
+        let mut filter = AcceptFilterArg {
+            af_name: [0; 16],
+            af_arg: [0; 256 - 16],
+        };
+        copy(&mut filter.af_name, name)?;
+        copy(&mut filter.af_arg, arg)?;
+        Ok(filter)
+    }
+
+    fn field(buf: &[libc::c_char]) -> &std::ffi::OsStr {
+        use std::os::unix::ffi::OsStrExt;
+
+        let bytes = unsafe {
+            slice::from_raw_parts(buf.as_ptr() as *const u8, buf.len())
+        };
+        let len = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+        std::ffi::OsStr::from_bytes(&bytes[..len])
+    }
+
+    /// The name of the filter.
+    pub fn name(&self) -> &std::ffi::OsStr {
+        Self::field(&self.af_name)
+    }
+
+    /// The argument passed to the filter.
+    pub fn arg(&self) -> &std::ffi::OsStr {
+        Self::field(&self.af_arg)
+    }
+}
+
+feature! {
+#![feature = "net"]
+/// The set of ICMPv6 message types that a raw ICMPv6 socket receives, for the
+/// [`Icmp6Filter`](sockopt::Icmp6Filter) socket option.
+///
+/// This has the layout of `struct icmp6_filter`.  Linux stores the types to
+/// block where the BSDs store the types to pass, which this hides.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use nix::sys::socket::*;
+/// # let fd = 0;
+/// // Only receive echo replies
+/// let mut filter = Icmp6Filter::block_all();
+/// filter.pass(129);
+/// setsockopt(fd, sockopt::Icmp6Filter, &filter).unwrap();
+/// ```
+#[cfg(any(
+    target_os = "android",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+))]
+#[cfg_attr(docsrs, doc(cfg(all())))]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Icmp6Filter {
+    icmp6_filt: [u32; 8],
+}
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+))]
+impl Icmp6Filter {
+    // Whether a set bit means that the type passes
+    const SET_PASSES: bool =
+        !cfg!(any(target_os = "android", target_os = "linux"));
+
+    fn fill(set: bool) -> Self {
+        let word = if set { u32::MAX } else { 0 };
+        Icmp6Filter { icmp6_filt: [word; 8] }
+    }
+
+    fn set(&mut self, icmp6_type: u8, passes: bool) {
+        let bit = 1 << (icmp6_type & 31);
+        let word = &mut self.icmp6_filt[usize::from(icmp6_type >> 5)];
+        if passes == Self::SET_PASSES {
+            *word |= bit;
+        } else {
+            *word &= !bit;
+        }
+    }
+
+    /// A filter that passes every message type.
+    pub fn pass_all() -> Self {
+        Self::fill(Self::SET_PASSES)
+    }
+
+    /// A filter that blocks every message type.
+    pub fn block_all() -> Self {
+        Self::fill(!Self::SET_PASSES)
+    }
+
+    /// Pass messages of type `icmp6_type`.
+    pub fn pass(&mut self, icmp6_type: u8) {
+        self.set(icmp6_type, true)
+    }
+
+    /// Block messages of type `icmp6_type`.
+    pub fn block(&mut self, icmp6_type: u8) {
+        self.set(icmp6_type, false)
+    }
+
+    /// Whether messages of type `icmp6_type` pass the filter.
+    pub fn will_pass(&self, icmp6_type: u8) -> bool {
+        let bit = 1 << (icmp6_type & 31);
+        let set = self.icmp6_filt[usize::from(icmp6_type >> 5)] & bit != 0;
+        set == Self::SET_PASSES
+    }
+}
+}
+
+feature! {
+#![feature = "net"]
+/// Request for multicast socket operations
+///
+/// This is a wrapper type around `ip_mreq`, or around `ip_mreqn` on Linux and
+/// Android so that the interface may also be chosen by index.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub struct IpMembershipRequest(libc::ip_mreqn);
 /// Request for multicast socket operations
 ///
 /// This is a wrapper type around `ip_mreq`.
 #[repr(transparent)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg(not(any(target_os = "android", target_os = "linux")))]
 pub struct IpMembershipRequest(libc::ip_mreq);
 
 impl IpMembershipRequest {
@@ -514,8 +951,67 @@ impl IpMembershipRequest {
             None => net::Ipv4Addr::UNSPECIFIED,
             Some(addr) => addr
         };
-        IpMembershipRequest(libc::ip_mreq {
+        cfg_if! {
+            if #[cfg(any(target_os = "android", target_os = "linux"))] {
+                IpMembershipRequest(libc::ip_mreqn {
+                    imr_multiaddr: ipv4addr_to_libc(group),
+                    imr_address: ipv4addr_to_libc(imr_addr),
+                    imr_ifindex: 0
+                })
+            } else {
+                IpMembershipRequest(libc::ip_mreq {
+                    imr_multiaddr: ipv4addr_to_libc(group),
+                    imr_interface: ipv4addr_to_libc(imr_addr)
+                })
+            }
+        }
+    }
+
+    /// Instantiate a new `IpMembershipRequest` for the interface with index
+    /// `ifindex`, as returned by
+    /// [`if_nametoindex`](crate::net::if_::if_nametoindex).
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[cfg_attr(docsrs, doc(cfg(all())))]
+    pub fn with_ifindex(group: net::Ipv4Addr, ifindex: u32) -> Self {
+        IpMembershipRequest(libc::ip_mreqn {
             imr_multiaddr: ipv4addr_to_libc(group),
+            imr_address: ipv4addr_to_libc(net::Ipv4Addr::UNSPECIFIED),
+            imr_ifindex: ifindex as libc::c_int
+        })
+    }
+}
+
+/// Request for source-specific multicast socket operations
+///
+/// This is a wrapper type around `ip_mreq_source`.
+#[cfg(any(target_os = "android",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos"))]
+#[cfg_attr(docsrs, doc(cfg(all())))]
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct IpSourceMembershipRequest(libc::ip_mreq_source);
+
+#[cfg(any(target_os = "android",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos"))]
+impl IpSourceMembershipRequest {
+    /// Instantiate a new `IpSourceMembershipRequest`, to receive datagrams
+    /// sent to `group` only from `source`.
+    ///
+    /// If `interface` is `None`, then `Ipv4Addr::any()` will be used for the interface.
+    pub fn new(group: net::Ipv4Addr,
+               source: net::Ipv4Addr,
+               interface: Option<net::Ipv4Addr>) -> Self
+    {
+        let imr_addr = interface.unwrap_or(net::Ipv4Addr::UNSPECIFIED);
+        IpSourceMembershipRequest(libc::ip_mreq_source {
+            imr_multiaddr: ipv4addr_to_libc(group),
+            imr_sourceaddr: ipv4addr_to_libc(source),
             imr_interface: ipv4addr_to_libc(imr_addr)
         })
     }
@@ -580,11 +1076,42 @@ macro_rules! cmsg_space {
     }
 }
 
+/// Compute the buffer capacity needed to receive control messages with the
+/// given payload sizes, in bytes.
+///
+/// This is the runtime counterpart of [`cmsg_space!`](crate::cmsg_space), for
+/// when the number of expected messages or file descriptors is only known at
+/// runtime.
+///
+/// # Examples
+///
+/// ```
+/// # use nix::sys::socket::*;
+/// # use nix::sys::time::TimeVal;
+/// # use std::mem::size_of;
+/// # use std::os::unix::io::RawFd;
+/// let nfds = 3;
+/// let space = cmsg_space_len(&[nfds * size_of::<RawFd>(),
+///                              size_of::<TimeVal>()]);
+/// let cmsg_buffer = Vec::<u8>::with_capacity(space);
+/// # assert_eq!(cmsg_buffer.capacity(), space);
+/// # assert_eq!(space, nix::cmsg_space!([RawFd; 3], TimeVal).capacity());
+/// ```
+pub fn cmsg_space_len(payload_lens: &[usize]) -> usize {
+    payload_lens.iter()
+        // CMSG_SPACE is always safe
+        .map(|&len| unsafe { CMSG_SPACE(len as c_uint) } as usize)
+        .sum()
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct RecvMsg<'a, S> {
     pub bytes: usize,
     cmsghdr: Option<&'a cmsghdr>,
     pub address: Option<S>,
+    /// Flags describing the received message.  `MSG_TRUNC` indicates that the
+    /// datagram was larger than the supplied buffers, and `MSG_CTRUNC` that
+    /// some control messages were discarded for lack of space.
     pub flags: MsgFlags,
     mhdr: msghdr,
 }
@@ -773,6 +1300,35 @@ pub enum ControlMessageOwned {
     #[cfg_attr(docsrs, doc(cfg(feature = "net")))]
     Ipv6OrigDstAddr(libc::sockaddr_in6),
 
+    /// The Type-Of-Service field of a received IPv4 packet.
+    ///
+    /// [`IpRecvTos`](crate::sys::socket::sockopt::IpRecvTos) should be
+    /// enabled on the socket to receive it.
+    #[cfg(any(
+        target_os = "android",
+        target_os = "freebsd",
+        target_os = "ios",
+        target_os = "linux",
+        target_os = "macos",
+    ))]
+    #[cfg(feature = "net")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "net")))]
+    Ipv4Tos(u8),
+    /// The traffic class field of a received IPv6 packet.
+    ///
+    /// [`Ipv6RecvTClass`](crate::sys::socket::sockopt::Ipv6RecvTClass)
+    /// should be enabled on the socket to receive it.
+    #[cfg(any(
+        target_os = "android",
+        target_os = "freebsd",
+        target_os = "ios",
+        target_os = "linux",
+        target_os = "macos",
+    ))]
+    #[cfg(feature = "net")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "net")))]
+    Ipv6TClass(i32),
+
     /// UDP Generic Receive Offload (GRO) allows receiving multiple UDP
     /// packets from a single sender.
     /// Fixed-size payloads are following one by one in a receive buffer.
@@ -966,6 +1522,28 @@ impl ControlMessageOwned {
                 let dl = ptr::read_unaligned(p as *const libc::sockaddr_in6);
                 ControlMessageOwned::Ipv6OrigDstAddr(dl)
             },
+            #[cfg(any(target_os = "android", target_os = "linux"))]
+            #[cfg(feature = "net")]
+            (libc::IPPROTO_IP, libc::IP_TOS) => {
+                ControlMessageOwned::Ipv4Tos(*p)
+            },
+            #[cfg(any(target_os = "freebsd", target_os = "ios", target_os = "macos"))]
+            #[cfg(feature = "net")]
+            (libc::IPPROTO_IP, libc::IP_RECVTOS) => {
+                ControlMessageOwned::Ipv4Tos(*p)
+            },
+            #[cfg(any(
+                target_os = "android",
+                target_os = "freebsd",
+                target_os = "ios",
+                target_os = "linux",
+                target_os = "macos",
+            ))]
+            #[cfg(feature = "net")]
+            (libc::IPPROTO_IPV6, libc::IPV6_TCLASS) => {
+                let tclass = ptr::read_unaligned(p as *const i32);
+                ControlMessageOwned::Ipv6TClass(tclass)
+            },
             (_, _) => {
                 let sl = slice::from_raw_parts(p, len);
                 let ucmsg = UnknownCmsg(*header, Vec::<u8>::from(sl));
@@ -1823,6 +2401,193 @@ pub fn recvmsg<'a, 'outer, 'inner, S>(fd: RawFd, iov: &'outer mut [IoSliceMut<'i
 
     Ok(unsafe { read_mhdr(mhdr, r, msg_controllen, address.assume_init(), &mut cmsg_buffer) })
 }
+
+/// Send file descriptors over a Unix-domain socket, along with some data.
+///
+/// This is a convenience wrapper around [`sendmsg`] with a single
+/// [`ControlMessage::ScmRights`] message.  Some platforms can't pass file
+/// descriptors over a stream socket without at least one byte of `payload`.
+///
+/// Returns the number of bytes of `payload` sent.
+///
+/// # Examples
+///
+/// ```
+/// # use nix::sys::socket::*;
+/// # use nix::unistd::pipe;
+/// let (fd1, fd2) = socketpair(AddressFamily::Unix, SockType::Stream, None,
+///     SockFlag::empty())
+///     .unwrap();
+/// let (r, w) = pipe().unwrap();
+/// send_fds(fd1, &[r, w], b"pipe").unwrap();
+///
+/// let mut buf = [0u8; 4];
+/// let (bytes, fds) = recv_fds(fd2, &mut buf, 2).unwrap();
+/// assert_eq!(&buf[..bytes], b"pipe");
+/// assert_eq!(fds.len(), 2);
+/// ```
+pub fn send_fds(fd: RawFd, fds: &[RawFd], payload: &[u8]) -> Result<usize> {
+    let iov = [IoSlice::new(payload)];
+    let cmsgs = [ControlMessage::ScmRights(fds)];
+    let cmsgs = if fds.is_empty() { &cmsgs[..0] } else { &cmsgs[..] };
+    sendmsg::<()>(fd, &iov, cmsgs, MsgFlags::empty(), None)
+}
+
+/// Receive data, and up to `max_fds` file descriptors, from a Unix-domain
+/// socket.
+///
+/// This is a convenience wrapper around [`recvmsg`] that sizes the control
+/// message buffer and collects any [`ControlMessageOwned::ScmRights`]
+/// messages.  The received file descriptors have the close-on-exec flag set.
+/// On platforms without `MSG_CMSG_CLOEXEC`, that is done with `fcntl(2)`
+/// after they are received, so another thread could race to `exec` first.
+///
+/// Returns the number of bytes received into `buf`, and the received file
+/// descriptors, which the caller is responsible for closing.
+///
+/// # Errors
+///
+/// If the sender passed more than `max_fds` file descriptors, then some of
+/// them may have been discarded by the kernel.  In that case, all of the file
+/// descriptors that were received are closed, and `EMSGSIZE` is returned.
+pub fn recv_fds(fd: RawFd, buf: &mut [u8], max_fds: usize)
+    -> Result<(usize, Vec<RawFd>)>
+{
+    let space = cmsg_space_len(&[max_fds * mem::size_of::<RawFd>()]);
+    let mut cmsg_buffer = Vec::with_capacity(space);
+    cfg_if! {
+        if #[cfg(any(target_os = "android",
+                     target_os = "dragonfly",
+                     target_os = "freebsd",
+                     target_os = "linux",
+                     target_os = "netbsd",
+                     target_os = "openbsd"))] {
+            let flags = MsgFlags::MSG_CMSG_CLOEXEC;
+        } else {
+            let flags = MsgFlags::empty();
+        }
+    }
+
+    let mut iov = [IoSliceMut::new(buf)];
+    let msg = recvmsg::<()>(fd, &mut iov, Some(&mut cmsg_buffer), flags)?;
+    let mut fds = Vec::new();
+    for cmsg in msg.cmsgs() {
+        if let ControlMessageOwned::ScmRights(received) = cmsg {
+            fds.extend(received);
+        }
+    }
+
+    if msg.flags.contains(MsgFlags::MSG_CTRUNC) || fds.len() > max_fds {
+        for fd in fds {
+            unsafe { libc::close(fd) };
+        }
+        return Err(Errno::EMSGSIZE);
+    }
+    if flags.is_empty() {
+        for &fd in &fds {
+            let r = unsafe {
+                libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC)
+            };
+            if let Err(e) = Errno::result(r) {
+                for fd in fds {
+                    unsafe { libc::close(fd) };
+                }
+                return Err(e);
+            }
+        }
+    }
+    Ok((msg.bytes, fds))
+}
+
+// Not yet exported by libc
+#[cfg(target_os = "linux")]
+#[cfg(feature = "net")]
+const SO_EE_ORIGIN_ZEROCOPY: u8 = 5;
+#[cfg(target_os = "linux")]
+#[cfg(feature = "net")]
+const SO_EE_CODE_ZEROCOPY_COPIED: u8 = 1;
+
+/// A notification read from a socket's error queue by [`recv_errqueue`].
+#[cfg(target_os = "linux")]
+#[cfg(feature = "net")]
+#[cfg_attr(docsrs, doc(cfg(feature = "net")))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ErrQueueMsg {
+    /// The `MSG_ZEROCOPY` sends numbered `first` through `last`, inclusive,
+    /// have completed, so their buffers may be reused.  Sends are numbered
+    /// from 0, per socket.
+    ZerocopyCompletion {
+        /// The first completed send
+        first: u32,
+        /// The last completed send
+        last: u32,
+        /// The kernel copied the data of at least one of these sends after
+        /// all, for example because it was sent over the loopback interface.
+        copied: bool,
+    },
+    /// Any other error, such as an ICMP error reported with
+    /// [`Ipv4RecvErr`](sockopt::Ipv4RecvErr) enabled.
+    Error {
+        /// The error, as reported by the kernel
+        err: libc::sock_extended_err,
+        /// The address of the node that reported the error, if it came from
+        /// the network
+        offender: Option<SockaddrStorage>,
+    },
+}
+
+/// Read one notification from the error queue of the IPv4 or IPv6 socket
+/// `fd`, using `recvmsg` with `MSG_ERRQUEUE`.
+///
+/// Reading the error queue never blocks: if it is empty, `EAGAIN` is
+/// returned.  Use `poll` without any events to wait for it, since an error
+/// queue entry is always reported as `POLLERR`.
+///
+/// # References
+///
+/// * [msg_zerocopy](https://www.kernel.org/doc/html/latest/networking/msg_zerocopy.html)
+/// * [ip(7)](https://man7.org/linux/man-pages/man7/ip.7.html)
+#[cfg(target_os = "linux")]
+#[cfg(feature = "net")]
+#[cfg_attr(docsrs, doc(cfg(feature = "net")))]
+pub fn recv_errqueue(fd: RawFd) -> Result<ErrQueueMsg> {
+    let mut cmsg_buffer =
+        cmsg_space!(libc::sock_extended_err, libc::sockaddr_in6);
+    // The payload of the failed datagram, if any, is not needed
+    let mut iov = [IoSliceMut::new(&mut [])];
+    let msg = recvmsg::<()>(fd, &mut iov, Some(&mut cmsg_buffer),
+                            MsgFlags::MSG_ERRQUEUE)?;
+    for cmsg in msg.cmsgs() {
+        let (err, offender) = match cmsg {
+            ControlMessageOwned::Ipv4RecvErr(err, addr) => {
+                let offender = addr.and_then(|a| unsafe {
+                    SockaddrStorage::from_raw(
+                        &a as *const _ as *const sockaddr,
+                        Some(mem::size_of_val(&a) as socklen_t))
+                });
+                (err, offender)
+            },
+            ControlMessageOwned::Ipv6RecvErr(err, addr) => {
+                let offender = addr.and_then(|a| unsafe {
+                    SockaddrStorage::from_raw(
+                        &a as *const _ as *const sockaddr,
+                        Some(mem::size_of_val(&a) as socklen_t))
+                });
+                (err, offender)
+            },
+            _ => continue
+        };
+        if err.ee_origin == SO_EE_ORIGIN_ZEROCOPY && err.ee_errno == 0 {
+            return Ok(ErrQueueMsg::ZerocopyCompletion {
+                first: err.ee_info,
+                last: err.ee_data,
+                copied: err.ee_code & SO_EE_CODE_ZEROCOPY_COPIED != 0,
+            });
+        }
+        return Ok(ErrQueueMsg::Error { err, offender });
+    }
+    Err(Errno::ENOMSG)
+}
 }
 
 
@@ -1958,6 +2723,61 @@ pub fn recv(sockfd: RawFd, buf: &mut [u8], flags: MsgFlags) -> Result<usize> {
     }
 }
 
+/// Like [`recv`], but receives into a possibly uninitialized buffer.
+///
+/// Returns the part of `buf` that was filled in, so large receive buffers
+/// need not be zeroed before every call.  If `flags` includes `MSG_TRUNC`, the
+/// returned slice is still limited to `buf`.
+///
+/// # Examples
+///
+/// ```
+/// # use nix::sys::socket::*;
+/// # use nix::unistd::write;
+/// # use std::mem::MaybeUninit;
+/// let (a, b) = socketpair(AddressFamily::Unix, SockType::Stream, None,
+///                         SockFlag::empty()).unwrap();
+/// write(a, b"hello").unwrap();
+/// let mut buf = [MaybeUninit::<u8>::uninit(); 64];
+/// let data = recv_uninit(b, &mut buf, MsgFlags::empty()).unwrap();
+/// assert_eq!(data, b"hello");
+/// ```
+pub fn recv_uninit(
+    sockfd: RawFd,
+    buf: &mut [mem::MaybeUninit<u8>],
+    flags: MsgFlags
+) -> Result<&mut [u8]> {
+    let ret = unsafe {
+        libc::recv(
+            sockfd,
+            buf.as_mut_ptr() as *mut c_void,
+            buf.len() as size_t,
+            flags.bits())
+    };
+    let len = Errno::result(ret)?.min(buf.len() as isize) as usize;
+
+    // Safe because the kernel initialized the first `len` bytes
+    Ok(unsafe { slice::from_raw_parts_mut(buf.as_mut_ptr() as *mut u8, len) })
+}
+
+/// Return the size of the next datagram queued on `sockfd`, without
+/// receiving it.
+///
+/// This lets a caller grow its buffer before the real read, rather than have
+/// the datagram truncated.  Like `recv`, it blocks until a datagram arrives
+/// unless the socket is nonblocking.
+///
+/// # References
+///
+/// See `MSG_TRUNC` in [udp(7)](https://man7.org/linux/man-pages/man7/udp.7.html).
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[cfg_attr(docsrs, doc(cfg(all())))]
+pub fn peek_datagram_len(sockfd: RawFd) -> Result<usize> {
+    // With MSG_TRUNC, Linux returns the datagram's real length even though the
+    // buffer is empty.
+    recv(sockfd, &mut [], MsgFlags::MSG_PEEK | MsgFlags::MSG_TRUNC)
+}
+
 /// Receive data from a connectionless or connection-oriented socket. Returns
 /// the number of bytes read and, for connectionless sockets,  the socket
 /// address of the sender.
@@ -1985,6 +2805,35 @@ pub fn recvfrom<T:SockaddrLike>(sockfd: RawFd, buf: &mut [u8])
     }
 }
 
+/// Like [`recvfrom`], but receives into a possibly uninitialized buffer.
+///
+/// Returns the part of `buf` that was filled in and, for connectionless
+/// sockets, the socket address of the sender.
+pub fn recvfrom_uninit<T: SockaddrLike>(
+    sockfd: RawFd,
+    buf: &mut [mem::MaybeUninit<u8>]
+) -> Result<(&mut [u8], Option<T>)> {
+    unsafe {
+        let mut addr = mem::MaybeUninit::<T>::uninit();
+        let mut len = mem::size_of_val(&addr) as socklen_t;
+
+        let ret = Errno::result(libc::recvfrom(
+            sockfd,
+            buf.as_mut_ptr() as *mut c_void,
+            buf.len() as size_t,
+            0,
+            addr.as_mut_ptr() as *mut libc::sockaddr,
+            &mut len as *mut socklen_t))? as usize;
+
+        // The kernel initialized the first `ret` bytes
+        let data = slice::from_raw_parts_mut(buf.as_mut_ptr() as *mut u8, ret);
+        Ok((data, T::from_raw(
+            addr.assume_init().as_ptr() as *const libc::sockaddr,
+            Some(len))
+        ))
+    }
+}
+
 /// Send a message to a socket
 ///
 /// [Further reading](https://pubs.opengroup.org/onlinepubs/9699919799/functions/sendto.html)
@@ -2064,6 +2913,244 @@ pub fn setsockopt<O: SetSockOpt>(fd: RawFd, opt: O, val: &O::Val) -> Result<()>
     opt.set(fd, val)
 }
 
+/// Get the value of an arbitrary socket option, as raw bytes.
+///
+/// This is an escape hatch for options that don't yet have a typed
+/// equivalent in [`sockopt`].  `val` receives the option's value, and the
+/// length reported by the kernel is returned.
+///
+/// [Further reading](https://pubs.opengroup.org/onlinepubs/9699919799/functions/getsockopt.html)
+///
+/// To give such an option the same interface as the built-in ones, define it
+/// with [`sockopt_raw!`](crate::sockopt_raw) instead.
+///
+/// # Examples
+///
+/// ```
+/// # use nix::libc;
+/// # use nix::sys::socket::*;
+/// let fd = socket(AddressFamily::Unix, SockType::Datagram, SockFlag::empty(),
+///     None).unwrap();
+/// let mut buf = [0u8; 4];
+/// let len = getsockopt_raw(fd, libc::SOL_SOCKET, libc::SO_TYPE, &mut buf)
+///     .unwrap();
+/// assert_eq!(len, buf.len());
+/// assert_eq!(i32::from_ne_bytes(buf), libc::SOCK_DGRAM);
+/// ```
+pub fn getsockopt_raw(
+    fd: RawFd,
+    level: c_int,
+    name: c_int,
+    val: &mut [u8]
+) -> Result<usize> {
+    let mut len = val.len() as socklen_t;
+    let res = unsafe {
+        libc::getsockopt(fd, level, name, val.as_mut_ptr() as *mut c_void,
+                         &mut len)
+    };
+    Errno::result(res).map(|_| len as usize)
+}
+
+/// Set the value of an arbitrary socket option from raw bytes.
+///
+/// This is an escape hatch for options that don't yet have a typed
+/// equivalent in [`sockopt`].  To give such an option the same interface as
+/// the built-in ones, define it with [`sockopt_raw!`](crate::sockopt_raw)
+/// instead.
+///
+/// [Further reading](https://pubs.opengroup.org/onlinepubs/9699919799/functions/setsockopt.html)
+pub fn setsockopt_raw(
+    fd: RawFd,
+    level: c_int,
+    name: c_int,
+    val: &[u8]
+) -> Result<()> {
+    let res = unsafe {
+        libc::setsockopt(fd, level, name, val.as_ptr() as *const c_void,
+                         val.len() as socklen_t)
+    };
+    Errno::result(res).map(drop)
+}
+
+/// Define a socket option that Nix doesn't know about, for use with
+/// [`getsockopt`](crate::sys::socket::getsockopt) and
+/// [`setsockopt`](crate::sys::socket::setsockopt).
+///
+/// This generates a unit struct, and implements
+/// [`GetSockOpt`](crate::sys::socket::GetSockOpt),
+/// [`SetSockOpt`](crate::sys::socket::SetSockOpt), or both for it on top of
+/// [`getsockopt_raw`](crate::sys::socket::getsockopt_raw) and
+/// [`setsockopt_raw`](crate::sys::socket::setsockopt_raw).
+///
+/// The arguments to this macro are:
+///
+/// * The struct's visibility and name, optionally preceded by attributes
+/// * `GetOnly`, `SetOnly` or `Both`: which of the traits to implement
+/// * The option's level, such as `libc::SOL_SOCKET`
+/// * The option's name, such as `libc::SO_RCVBUF`
+/// * The type of the option's value
+///
+/// The value is passed to the kernel as the raw bytes of the type, so it must
+/// be a plain C type without padding, such as `libc::c_int` or a `#[repr(C)]`
+/// struct of them, for which any bit pattern is valid.  Getting the option
+/// panics if the kernel's value has a different size.
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate nix;
+/// # use nix::libc;
+/// # use nix::sys::socket::*;
+/// sockopt_raw!(
+///     /// Whether the socket accepts connections
+///     AcceptConn, GetOnly, libc::SOL_SOCKET, libc::SO_ACCEPTCONN,
+///     libc::c_int);
+///
+/// # fn main() {
+/// let fd = socket(AddressFamily::Unix, SockType::Stream, SockFlag::empty(),
+///     None).unwrap();
+/// assert_eq!(getsockopt(fd, AcceptConn).unwrap(), 0);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! sockopt_raw {
+    (@get $name:ident, $level:expr, $flag:expr, $ty:ty) => {
+        impl $crate::sys::socket::GetSockOpt for $name {
+            type Val = $ty;
+
+            fn get(&self, fd: ::std::os::unix::io::RawFd)
+                -> $crate::Result<$ty>
+            {
+                let size = ::std::mem::size_of::<$ty>();
+                let mut val = ::std::mem::MaybeUninit::<$ty>::zeroed();
+                // The zeroed value is initialized, and outlives buf
+                let buf = unsafe {
+                    ::std::slice::from_raw_parts_mut(
+                        val.as_mut_ptr() as *mut u8, size)
+                };
+                let len = $crate::sys::socket::getsockopt_raw(fd, $level,
+                                                              $flag, buf)?;
+                assert_eq!(len, size, "invalid getsockopt implementation");
+                // The kernel filled in a whole plain C value
+                Ok(unsafe { val.assume_init() })
+            }
+        }
+    };
+
+    (@set $name:ident, $level:expr, $flag:expr, $ty:ty) => {
+        impl $crate::sys::socket::SetSockOpt for $name {
+            type Val = $ty;
+
+            fn set(&self, fd: ::std::os::unix::io::RawFd, val: &$ty)
+                -> $crate::Result<()>
+            {
+                // A plain C value without padding is all initialized bytes
+                let buf = unsafe {
+                    ::std::slice::from_raw_parts(
+                        val as *const $ty as *const u8,
+                        ::std::mem::size_of::<$ty>())
+                };
+                $crate::sys::socket::setsockopt_raw(fd, $level, $flag, buf)
+            }
+        }
+    };
+
+    ($(#[$attr:meta])* $vis:vis $name:ident, GetOnly, $level:expr,
+     $flag:expr, $ty:ty) =>
+    {
+        $(#[$attr])*
+        #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+        $vis struct $name;
+
+        $crate::sockopt_raw!(@get $name, $level, $flag, $ty);
+    };
+
+    ($(#[$attr:meta])* $vis:vis $name:ident, SetOnly, $level:expr,
+     $flag:expr, $ty:ty) =>
+    {
+        $(#[$attr])*
+        #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+        $vis struct $name;
+
+        $crate::sockopt_raw!(@set $name, $level, $flag, $ty);
+    };
+
+    ($(#[$attr:meta])* $vis:vis $name:ident, Both, $level:expr,
+     $flag:expr, $ty:ty) =>
+    {
+        $(#[$attr])*
+        #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+        $vis struct $name;
+
+        $crate::sockopt_raw!(@get $name, $level, $flag, $ty);
+        $crate::sockopt_raw!(@set $name, $level, $flag, $ty);
+    };
+}
+
+/// Get the credentials of the peer connected to the Unix domain socket `fd`.
+///
+/// The credentials are those the peer had when it called `connect` or
+/// `socketpair`.  This uses `SO_PEERCRED` on Linux and Android, `getpeereid`
+/// and `LOCAL_PEERPID` on macOS and iOS, and `getpeereid` elsewhere.  Only
+/// Linux, Android, macOS and iOS report the peer's process ID.
+///
+/// # Examples
+///
+/// ```
+/// # use nix::sys::socket::*;
+/// # use nix::unistd::{getegid, geteuid};
+/// let (a, _b) = socketpair(AddressFamily::Unix, SockType::Stream, None,
+///                          SockFlag::empty()).unwrap();
+/// let cred = peer_credentials(a).unwrap();
+/// assert_eq!(cred.uid(), geteuid().as_raw());
+/// assert_eq!(cred.gid(), getegid().as_raw());
+/// ```
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+#[cfg_attr(docsrs, doc(cfg(all())))]
+pub fn peer_credentials(fd: RawFd) -> Result<PeerCred> {
+    cfg_if! {
+        if #[cfg(any(target_os = "android", target_os = "linux"))] {
+            let cred = getsockopt(fd, sockopt::PeerCredentials)?;
+            Ok(PeerCred {
+                uid: cred.uid(),
+                gid: cred.gid(),
+                pid: Some(cred.pid()),
+            })
+        } else {
+            let mut uid = 0;
+            let mut gid = 0;
+            let res = unsafe { libc::getpeereid(fd, &mut uid, &mut gid) };
+            Errno::result(res)?;
+
+            #[cfg(any(target_os = "ios", target_os = "macos"))]
+            let pid = {
+                let mut pid: libc::pid_t = 0;
+                let mut len = mem::size_of::<libc::pid_t>() as socklen_t;
+                let res = unsafe {
+                    libc::getsockopt(fd, libc::SOL_LOCAL, libc::LOCAL_PEERPID,
+                                     &mut pid as *mut _ as *mut c_void,
+                                     &mut len)
+                };
+                Errno::result(res)?;
+                Some(pid)
+            };
+            #[cfg(not(any(target_os = "ios", target_os = "macos")))]
+            let pid = None;
+
+            Ok(PeerCred { uid, gid, pid })
+        }
+    }
+}
+
 /// Get the address of the peer connected to the socket `fd`.
 ///
 /// [Further reading](https://pubs.opengroup.org/onlinepubs/9699919799/functions/getpeername.html)
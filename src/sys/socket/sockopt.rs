@@ -255,6 +255,17 @@ sockopt_impl!(
     /// Permits multiple AF_INET or AF_INET6 sockets to be bound to an
     /// identical socket address.
     ReusePort, Both, libc::SOL_SOCKET, libc::SO_REUSEPORT, bool);
+#[cfg(target_os = "freebsd")]
+sockopt_impl!(
+    /// Like [`ReusePort`], but load balances incoming connections and
+    /// datagrams across the sockets bound to the same address.
+    ReusePortLb, Both, libc::SOL_SOCKET, libc::SO_REUSEPORT_LB, bool);
+#[cfg(target_os = "freebsd")]
+sockopt_impl!(
+    /// The accept filter of a listening socket.  Getting it fails with
+    /// `EINVAL` if the socket has none.
+    AcceptFilter, Both, libc::SOL_SOCKET, libc::SO_ACCEPTFILTER,
+    super::AcceptFilterArg);
 #[cfg(feature = "net")]
 sockopt_impl!(
     #[cfg_attr(docsrs, doc(cfg(feature = "net")))]
@@ -329,6 +340,35 @@ sockopt_impl!(
     /// Set or read a boolean integer argument that determines whether sent
     /// multicast packets should be looped back to the local sockets.
     IpMulticastLoop, Both, libc::IPPROTO_IP, libc::IP_MULTICAST_LOOP, bool);
+#[cfg(feature = "net")]
+sockopt_impl!(
+    #[cfg_attr(docsrs, doc(cfg(feature = "net")))]
+    /// Set or read the local address of the interface used for outgoing
+    /// multicast packets.
+    IpMulticastIf, Both, libc::IPPROTO_IP, libc::IP_MULTICAST_IF,
+    std::net::Ipv4Addr, GetIpv4Addr, SetIpv4Addr);
+#[cfg(any(target_os = "android",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos"))]
+#[cfg(feature = "net")]
+sockopt_impl!(
+    #[cfg_attr(docsrs, doc(cfg(feature = "net")))]
+    /// Join a multicast group, receiving only from a single source.
+    IpAddSourceMembership, SetOnly, libc::IPPROTO_IP,
+    libc::IP_ADD_SOURCE_MEMBERSHIP, super::IpSourceMembershipRequest);
+#[cfg(any(target_os = "android",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos"))]
+#[cfg(feature = "net")]
+sockopt_impl!(
+    #[cfg_attr(docsrs, doc(cfg(feature = "net")))]
+    /// Stop receiving from a source joined with `IpAddSourceMembership`.
+    IpDropSourceMembership, SetOnly, libc::IPPROTO_IP,
+    libc::IP_DROP_SOURCE_MEMBERSHIP, super::IpSourceMembershipRequest);
 #[cfg(any(target_os = "android", target_os = "fuchsia", target_os = "linux"))]
 #[cfg(feature = "net")]
 sockopt_impl!(
@@ -429,6 +469,37 @@ sockopt_impl!(
     /// data may remain unacknowledged before TCP will forcibly close the
     /// corresponding connection
     TcpUserTimeout, Both, libc::IPPROTO_TCP, libc::TCP_USER_TIMEOUT, u32);
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[cfg(feature = "net")]
+sockopt_impl!(
+    #[cfg_attr(docsrs, doc(cfg(feature = "net")))]
+    /// Enables TCP Fast Open on a listening socket.  The value is the
+    /// maximum length of the queue of pending Fast Open requests.
+    TcpFastOpen, Both, libc::IPPROTO_TCP, libc::TCP_FASTOPEN, u32);
+#[cfg(target_os = "linux")]
+#[cfg(feature = "net")]
+sockopt_impl!(
+    #[cfg_attr(docsrs, doc(cfg(feature = "net")))]
+    /// Enables TCP Fast Open on a client socket, so that data written right
+    /// after `connect` is carried in the SYN.
+    TcpFastOpenConnect, Both, libc::IPPROTO_TCP, libc::TCP_FASTOPEN_CONNECT,
+    bool);
+#[cfg(target_os = "linux")]
+#[cfg(feature = "net")]
+sockopt_impl!(
+    #[cfg_attr(docsrs, doc(cfg(feature = "net")))]
+    /// Statistics about a TCP connection, such as its round trip time and
+    /// congestion window.
+    TcpInfoOpt, GetOnly, libc::IPPROTO_TCP, libc::TCP_INFO, super::TcpInfo,
+    GetPartialStruct<super::TcpInfo>);
+#[cfg(any(target_os = "ios", target_os = "macos"))]
+#[cfg(feature = "net")]
+sockopt_impl!(
+    #[cfg_attr(docsrs, doc(cfg(feature = "net")))]
+    /// Statistics about a TCP connection, such as its round trip time and
+    /// congestion window.
+    TcpConnectionInfo, GetOnly, libc::IPPROTO_TCP, libc::TCP_CONNECTION_INFO,
+    super::TcpInfo, GetPartialStruct<super::TcpInfo>);
 sockopt_impl!(
     /// Sets or gets the maximum socket receive buffer in bytes.
     RcvBuf, Both, libc::SOL_SOCKET, libc::SO_RCVBUF, usize);
@@ -458,6 +529,20 @@ sockopt_impl!(
 sockopt_impl!(
     /// Bind this socket to a particular device like “eth0”.
     BindToDevice, Both, libc::SOL_SOCKET, libc::SO_BINDTODEVICE, OsString<[u8; libc::IFNAMSIZ]>);
+#[cfg(any(target_os = "ios", target_os = "macos"))]
+#[cfg(feature = "net")]
+sockopt_impl!(
+    #[cfg_attr(docsrs, doc(cfg(feature = "net")))]
+    /// Bind this IPv4 socket to the interface with the given index, as
+    /// returned by [`if_nametoindex`](crate::net::if_::if_nametoindex).
+    IpBoundIf, Both, libc::IPPROTO_IP, libc::IP_BOUND_IF, u32);
+#[cfg(any(target_os = "ios", target_os = "macos"))]
+#[cfg(feature = "net")]
+sockopt_impl!(
+    #[cfg_attr(docsrs, doc(cfg(feature = "net")))]
+    /// Bind this IPv6 socket to the interface with the given index, as
+    /// returned by [`if_nametoindex`](crate::net::if_::if_nametoindex).
+    Ipv6BoundIf, Both, libc::IPPROTO_IPV6, libc::IPV6_BOUND_IF, u32);
 #[cfg(any(target_os = "android", target_os = "linux"))]
 #[cfg(feature = "net")]
 sockopt_impl!(
@@ -612,6 +697,64 @@ sockopt_impl!(
     /// The socket is restricted to sending and receiving IPv6 packets only.
     Ipv6V6Only, Both, libc::IPPROTO_IPV6, libc::IPV6_V6ONLY, bool);
 #[cfg(any(target_os = "android", target_os = "linux"))]
+sockopt_impl!(
+    /// Attach a classic BPF program that filters the packets received on this
+    /// socket.
+    AttachFilter, SetOnly, libc::SOL_SOCKET, libc::SO_ATTACH_FILTER,
+    super::BpfProgram, SetBpfProgram);
+#[cfg(any(target_os = "android", target_os = "linux"))]
+sockopt_impl!(
+    /// Attach a classic BPF program that picks which socket of this socket's
+    /// `SO_REUSEPORT` group receives each packet.  The program returns the
+    /// index of the socket within the group.
+    AttachReusePortCbpf, SetOnly, libc::SOL_SOCKET,
+    libc::SO_ATTACH_REUSEPORT_CBPF, super::BpfProgram, SetBpfProgram);
+// Only recent versions of libc export these
+#[cfg(target_os = "linux")]
+cfg_if! {
+    if #[cfg(any(target_arch = "sparc", target_arch = "sparc64"))] {
+        const SO_BUSY_POLL: c_int = 0x30;
+        const SO_INCOMING_CPU: c_int = 0x33;
+        const SO_ZEROCOPY: c_int = 0x3e;
+        const SO_PREFER_BUSY_POLL: c_int = 0x48;
+        const SO_BUSY_POLL_BUDGET: c_int = 0x49;
+    } else {
+        const SO_BUSY_POLL: c_int = 46;
+        const SO_INCOMING_CPU: c_int = 49;
+        const SO_ZEROCOPY: c_int = 60;
+        const SO_PREFER_BUSY_POLL: c_int = 69;
+        const SO_BUSY_POLL_BUDGET: c_int = 70;
+    }
+}
+#[cfg(target_os = "linux")]
+sockopt_impl!(
+    /// The CPU that last processed this socket's incoming packets.  Setting
+    /// it on the sockets of a `SO_REUSEPORT` group, before they are bound,
+    /// makes the kernel prefer the socket whose CPU handled the packet.
+    IncomingCpu, Both, libc::SOL_SOCKET, SO_INCOMING_CPU, libc::c_int);
+#[cfg(target_os = "linux")]
+sockopt_impl!(
+    /// Allow sending with
+    /// [`MSG_ZEROCOPY`](crate::sys::socket::MsgFlags::MSG_ZEROCOPY).
+    ZeroCopy, Both, libc::SOL_SOCKET, SO_ZEROCOPY, bool);
+#[cfg(target_os = "linux")]
+sockopt_impl!(
+    /// How long, in microseconds, a blocking receive busy polls the device
+    /// queue for packets before sleeping.  Raising it above the
+    /// `net.core.busy_read` sysctl requires `CAP_NET_ADMIN`.
+    BusyPoll, Both, libc::SOL_SOCKET, SO_BUSY_POLL, libc::c_int);
+#[cfg(target_os = "linux")]
+sockopt_impl!(
+    /// Prefer busy polling over interrupt-driven processing of the device
+    /// queue, when busy polling is enabled.
+    PreferBusyPoll, Both, libc::SOL_SOCKET, SO_PREFER_BUSY_POLL, bool);
+#[cfg(target_os = "linux")]
+sockopt_impl!(
+    /// The maximum number of packets processed in each busy poll.  Raising
+    /// it requires `CAP_NET_ADMIN`.
+    BusyPollBudget, SetOnly, libc::SOL_SOCKET, SO_BUSY_POLL_BUDGET,
+    libc::c_int);
+#[cfg(any(target_os = "android", target_os = "linux"))]
 sockopt_impl!(
     /// Enable extended reliable error message passing.
     Ipv4RecvErr, Both, libc::IPPROTO_IP, libc::IP_RECVERR, bool);
@@ -628,6 +771,110 @@ sockopt_impl!(
 sockopt_impl!(
     /// Set the unicast hop limit for the socket.
     Ipv6Ttl, Both, libc::IPPROTO_IPV6, libc::IPV6_UNICAST_HOPS, libc::c_int);
+#[cfg(any(
+    target_os = "android",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+))]
+#[cfg(feature = "net")]
+sockopt_impl!(
+    #[cfg_attr(docsrs, doc(cfg(feature = "net")))]
+    /// Set or retrieve the Type-Of-Service field, which holds the DSCP and
+    /// ECN bits, of every IPv4 packet sent from this socket.
+    IpTos, Both, libc::IPPROTO_IP, libc::IP_TOS, libc::c_int);
+#[cfg(any(
+    target_os = "android",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+))]
+#[cfg(feature = "net")]
+sockopt_impl!(
+    #[cfg_attr(docsrs, doc(cfg(feature = "net")))]
+    /// Set or retrieve the traffic class field, which holds the DSCP and ECN
+    /// bits, of every IPv6 packet sent from this socket.
+    Ipv6TClass, Both, libc::IPPROTO_IPV6, libc::IPV6_TCLASS, libc::c_int);
+#[cfg(any(
+    target_os = "android",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+))]
+#[cfg(feature = "net")]
+sockopt_impl!(
+    #[cfg_attr(docsrs, doc(cfg(feature = "net")))]
+    /// The `recvmsg(2)` call will return the Type-Of-Service field of each
+    /// IPv4 packet, as a
+    /// [`ControlMessageOwned::Ipv4Tos`](crate::sys::socket::ControlMessageOwned::Ipv4Tos).
+    IpRecvTos, Both, libc::IPPROTO_IP, libc::IP_RECVTOS, bool);
+#[cfg(any(
+    target_os = "android",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+))]
+#[cfg(feature = "net")]
+sockopt_impl!(
+    #[cfg_attr(docsrs, doc(cfg(feature = "net")))]
+    /// The `recvmsg(2)` call will return the traffic class field of each IPv6
+    /// packet, as a
+    /// [`ControlMessageOwned::Ipv6TClass`](crate::sys::socket::ControlMessageOwned::Ipv6TClass).
+    Ipv6RecvTClass, Both, libc::IPPROTO_IPV6, libc::IPV6_RECVTCLASS, bool);
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd",
+    target_os = "openbsd",
+))]
+#[cfg(feature = "net")]
+sockopt_impl!(
+    #[cfg_attr(docsrs, doc(cfg(feature = "net")))]
+    /// Packets sent on this raw IPv4 socket start with their own IP header.
+    IpHdrIncl, Both, libc::IPPROTO_IP, libc::IP_HDRINCL, bool);
+#[cfg(any(
+    target_os = "android",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+))]
+#[cfg(feature = "net")]
+sockopt_impl!(
+    #[cfg_attr(docsrs, doc(cfg(feature = "net")))]
+    /// The offset of the checksum field in the payload of a raw IPv6 socket.
+    /// The kernel computes the checksum of outgoing packets, and checks that
+    /// of incoming ones.  `-1` disables this.  Raw ICMPv6 sockets always
+    /// compute the checksum, at offset 2.
+    Ipv6Checksum, Both, libc::IPPROTO_IPV6, libc::IPV6_CHECKSUM, libc::c_int);
+// libc does not export this
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[cfg(feature = "net")]
+const ICMP6_FILTER: libc::c_int = 1;
+#[cfg(any(target_os = "freebsd", target_os = "ios", target_os = "macos"))]
+#[cfg(feature = "net")]
+const ICMP6_FILTER: libc::c_int = 18;
+#[cfg(any(
+    target_os = "android",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+))]
+#[cfg(feature = "net")]
+sockopt_impl!(
+    #[cfg_attr(docsrs, doc(cfg(feature = "net")))]
+    /// The ICMPv6 message types that a raw ICMPv6 socket receives.
+    Icmp6Filter, Both, libc::IPPROTO_ICMPV6, ICMP6_FILTER,
+    super::Icmp6Filter);
 #[cfg(any(target_os = "android", target_os = "freebsd", target_os = "linux"))]
 #[cfg(feature = "net")]
 sockopt_impl!(
@@ -762,6 +1009,41 @@ impl<T> Get<T> for GetStruct<T> {
     }
 }
 
+/// Getter for a `struct` that may have grown new fields over time.
+///
+/// An older kernel may fill in only a prefix of the struct.  The remainder
+/// will be zeroed.
+#[cfg(any(target_os = "ios", target_os = "linux", target_os = "macos"))]
+#[cfg(feature = "net")]
+struct GetPartialStruct<T> {
+    len: socklen_t,
+    val: MaybeUninit<T>,
+}
+
+#[cfg(any(target_os = "ios", target_os = "linux", target_os = "macos"))]
+#[cfg(feature = "net")]
+impl<T> Get<T> for GetPartialStruct<T> {
+    fn uninit() -> Self {
+        GetPartialStruct {
+            len: mem::size_of::<T>() as socklen_t,
+            val: MaybeUninit::zeroed(),
+        }
+    }
+
+    fn ffi_ptr(&mut self) -> *mut c_void {
+        self.val.as_mut_ptr() as *mut c_void
+    }
+
+    fn ffi_len(&mut self) -> *mut socklen_t {
+        &mut self.len
+    }
+
+    unsafe fn assume_init(self) -> T {
+        assert!(self.len as usize <= mem::size_of::<T>(), "invalid getsockopt implementation");
+        self.val.assume_init()
+    }
+}
+
 /// Setter for an arbitrary `struct`.
 struct SetStruct<'a, T: 'static> {
     ptr: &'a T,
@@ -781,6 +1063,39 @@ impl<'a, T> Set<'a, T> for SetStruct<'a, T> {
     }
 }
 
+/// Setter for a classic BPF program.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+struct SetBpfProgram {
+    prog: libc::sock_fprog,
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+impl<'a> Set<'a, super::BpfProgram> for SetBpfProgram {
+    fn new(val: &'a super::BpfProgram) -> SetBpfProgram {
+        use std::convert::TryFrom;
+
+        let instructions = val.instructions();
+        // BpfProgram::new ensures that the length fits
+        let len = libc::c_ushort::try_from(instructions.len())
+            .expect("BPF program too long");
+        // The kernel only reads the instructions, and does so before
+        // setsockopt returns, while `val` is still borrowed.
+        let prog = libc::sock_fprog {
+            len,
+            filter: instructions.as_ptr() as *mut libc::sock_filter,
+        };
+        SetBpfProgram { prog }
+    }
+
+    fn ffi_ptr(&self) -> *const c_void {
+        &self.prog as *const libc::sock_fprog as *const c_void
+    }
+
+    fn ffi_len(&self) -> socklen_t {
+        mem::size_of::<libc::sock_fprog>() as socklen_t
+    }
+}
+
 /// Getter for a boolean value.
 struct GetBool {
     len: socklen_t,
@@ -970,6 +1285,58 @@ impl<'a> Set<'a, OsString> for SetOsString<'a> {
     }
 }
 
+/// Getter for an IPv4 address, which the OS stores as an `in_addr`.
+#[cfg(feature = "net")]
+struct GetIpv4Addr {
+    len: socklen_t,
+    val: MaybeUninit<libc::in_addr>,
+}
+
+#[cfg(feature = "net")]
+impl Get<std::net::Ipv4Addr> for GetIpv4Addr {
+    fn uninit() -> Self {
+        GetIpv4Addr {
+            len: mem::size_of::<libc::in_addr>() as socklen_t,
+            val: MaybeUninit::uninit(),
+        }
+    }
+
+    fn ffi_ptr(&mut self) -> *mut c_void {
+        self.val.as_mut_ptr() as *mut c_void
+    }
+
+    fn ffi_len(&mut self) -> *mut socklen_t {
+        &mut self.len
+    }
+
+    unsafe fn assume_init(self) -> std::net::Ipv4Addr {
+        assert_eq!(self.len as usize, mem::size_of::<libc::in_addr>(), "invalid getsockopt implementation");
+        let addr = self.val.assume_init();
+        std::net::Ipv4Addr::from(u32::from_be(addr.s_addr))
+    }
+}
+
+/// Setter for an IPv4 address, which the OS stores as an `in_addr`.
+#[cfg(feature = "net")]
+struct SetIpv4Addr {
+    val: libc::in_addr,
+}
+
+#[cfg(feature = "net")]
+impl<'a> Set<'a, std::net::Ipv4Addr> for SetIpv4Addr {
+    fn new(val: &'a std::net::Ipv4Addr) -> SetIpv4Addr {
+        SetIpv4Addr { val: super::addr::ipv4addr_to_libc(*val) }
+    }
+
+    fn ffi_ptr(&self) -> *const c_void {
+        &self.val as *const libc::in_addr as *const c_void
+    }
+
+    fn ffi_len(&self) -> socklen_t {
+        mem::size_of::<libc::in_addr>() as socklen_t
+    }
+}
+
 
 #[cfg(test)]
 mod test {
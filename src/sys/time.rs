@@ -368,6 +368,51 @@ impl TimeSpec {
     pub const fn from_timespec(timespec: timespec) -> Self {
         Self(timespec)
     }
+
+    /// Construct a new `TimeSpec` from a number of milliseconds.
+    ///
+    /// `tv_sec` saturates at `time_t::MAX` if the value is too large.
+    pub const fn from_millis(milliseconds: u64) -> Self {
+        Self::from_secs_nanos(
+            milliseconds / 1_000,
+            (milliseconds % 1_000) * 1_000_000,
+        )
+    }
+
+    /// Construct a new `TimeSpec` from a number of microseconds.
+    ///
+    /// `tv_sec` saturates at `time_t::MAX` if the value is too large.
+    pub const fn from_micros(microseconds: u64) -> Self {
+        Self::from_secs_nanos(
+            microseconds / 1_000_000,
+            (microseconds % 1_000_000) * 1_000,
+        )
+    }
+
+    /// Construct a new `TimeSpec` from a number of nanoseconds.
+    ///
+    /// `tv_sec` saturates at `time_t::MAX` if the value is too large.
+    pub const fn from_nanos(nanoseconds: u64) -> Self {
+        Self::from_secs_nanos(
+            nanoseconds / NANOS_PER_SEC as u64,
+            nanoseconds % NANOS_PER_SEC as u64,
+        )
+    }
+
+    // `nanos` must already be normalized to less than one second.
+    const fn from_secs_nanos(secs: u64, nanos: u64) -> Self {
+        let secs = if secs > time_t::MAX as u64 {
+            time_t::MAX
+        } else {
+            secs as time_t
+        };
+        #[cfg_attr(target_env = "musl", allow(deprecated))]
+        // https://github.com/rust-lang/libc/issues/1848
+        TimeSpec(timespec {
+            tv_sec: secs,
+            tv_nsec: nanos as timespec_tv_nsec_t,
+        })
+    }
 }
 
 impl ops::Neg for TimeSpec {
@@ -733,6 +778,23 @@ mod test {
         assert_eq!(Duration::from(timespec), duration);
     }
 
+    #[test]
+    pub fn test_timespec_from_units() {
+        assert_eq!(TimeSpec::from_millis(1_500), TimeSpec::new(1, 500_000_000));
+        assert_eq!(TimeSpec::from_micros(2_000_001), TimeSpec::new(2, 1_000));
+        assert_eq!(TimeSpec::from_nanos(1_000_000_000), TimeSpec::new(1, 0));
+        assert_eq!(
+            TimeSpec::from_nanos(999_999_999),
+            TimeSpec::new(0, 999_999_999)
+        );
+        assert_eq!(TimeSpec::from_millis(0), TimeSpec::new(0, 0));
+        // tv_sec saturates where time_t is too small to hold the value
+        assert_eq!(
+            TimeSpec::from_millis(u64::MAX).tv_sec() as u64,
+            (u64::MAX / 1_000).min(libc::time_t::MAX as u64)
+        );
+    }
+
     #[test]
     pub fn test_timespec_neg() {
         let a = TimeSpec::seconds(1) + TimeSpec::nanoseconds(123);
@@ -3,7 +3,7 @@ use std::{
     ops::Deref,
     os::unix::io::AsRawFd,
     pin::Pin,
-    sync::atomic::{AtomicBool, Ordering},
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
     thread, time,
 };
 
@@ -14,7 +14,7 @@ use nix::{
         aio::*,
         signal::{
             sigaction, SaFlags, SigAction, SigHandler, SigSet, SigevNotify,
-            Signal,
+            SigevNotifyKind, Signal,
         },
         time::{TimeSpec, TimeValLike},
     },
@@ -154,6 +154,209 @@ mod aio_read {
         let _ = aior.as_mut().aio_return();
     }
 
+    /// Reaping a canceled read should report cancellation, not failure.
+    // glibc processes requests on the same fd one at a time, so the second
+    // read is guaranteed to still be queued, and therefore cancelable.
+    #[test]
+    #[cfg(all(target_os = "linux", target_env = "gnu"))]
+    fn reap_canceled() {
+        use nix::unistd::{close, pipe, write};
+
+        let (rfd, wfd) = pipe().unwrap();
+        let mut rbuf0 = vec![0; 1];
+        let mut rbuf1 = vec![0; 1];
+        let mut aior0 = Box::pin(AioRead::new(
+            rfd,
+            0,
+            &mut rbuf0,
+            0,
+            SigevNotify::SigevNone,
+        ));
+        let mut aior1 = Box::pin(AioRead::new(
+            rfd,
+            0,
+            &mut rbuf1,
+            0,
+            SigevNotify::SigevNone,
+        ));
+        aior0.as_mut().submit().unwrap();
+        aior1.as_mut().submit().unwrap();
+
+        assert_eq!(aior1.as_mut().cancel(), Ok(AioCancelStat::AioCanceled));
+        assert_eq!(poll_aio!(&mut aior1), Err(Errno::ECANCELED));
+        assert_eq!(aior1.as_mut().reap(), Ok(AioOutcome::Canceled));
+
+        write(wfd, b"x").unwrap();
+        assert_eq!(poll_aio!(&mut aior0), Ok(()));
+        assert_eq!(aior0.as_mut().reap(), Ok(AioOutcome::Completed(1)));
+        close(rfd).unwrap();
+        close(wfd).unwrap();
+    }
+
+    // Reading from an empty pipe stays in flight until data arrives
+    #[test]
+    #[cfg(all(target_os = "linux", target_env = "gnu"))]
+    fn progress() {
+        use nix::unistd::{close, pipe, write};
+
+        let (rfd, wfd) = pipe().unwrap();
+        let mut rbuf = vec![0; 1];
+        let mut aior = Box::pin(AioRead::new(
+            rfd,
+            0,
+            &mut rbuf,
+            0,
+            SigevNotify::SigevNone,
+        ));
+        assert_eq!(aior.as_mut().progress(), AioProgress::NotStarted);
+        aior.as_mut().submit().unwrap();
+        assert_eq!(aior.as_mut().progress(), AioProgress::InFlight);
+        write(wfd, b"x").unwrap();
+        poll_aio!(&mut aior).unwrap();
+        assert_eq!(aior.as_mut().progress(), AioProgress::Done);
+        aior.as_mut().aio_return().unwrap();
+        assert_eq!(aior.as_mut().progress(), AioProgress::Done);
+        close(rfd).unwrap();
+        close(wfd).unwrap();
+    }
+
+    /// A successfully canceled operation may be dropped without reaping it.
+    // glibc processes requests on the same fd one at a time, so the second
+    // read is guaranteed to still be queued, and therefore cancelable.
+    #[test]
+    #[cfg(all(target_os = "linux", target_env = "gnu"))]
+    fn drop_canceled() {
+        use nix::unistd::{close, pipe, write};
+
+        let (rfd, wfd) = pipe().unwrap();
+        let mut rbuf0 = vec![0; 1];
+        let mut rbuf1 = vec![0; 1];
+        let mut aior0 = Box::pin(AioRead::new(
+            rfd,
+            0,
+            &mut rbuf0,
+            0,
+            SigevNotify::SigevNone,
+        ));
+        aior0.as_mut().submit().unwrap();
+        {
+            let mut aior1 = Box::pin(AioRead::new(
+                rfd,
+                0,
+                &mut rbuf1,
+                0,
+                SigevNotify::SigevNone,
+            ));
+            aior1.as_mut().submit().unwrap();
+            assert_eq!(
+                aior1.as_mut().cancel(),
+                Ok(AioCancelStat::AioCanceled)
+            );
+            assert!(!aior1.in_progress());
+        }
+
+        write(wfd, b"x").unwrap();
+        assert_eq!(poll_aio!(&mut aior0), Ok(()));
+        assert_eq!(aior0.as_mut().aio_return(), Ok(1));
+        close(rfd).unwrap();
+        close(wfd).unwrap();
+    }
+
+    /// A canceled operation that hasn't been reaped yet should be recognized
+    /// as such by all of the helpers that inspect an operation's state.
+    // As in drop_canceled, the first read keeps the rest queued.
+    #[test]
+    #[cfg(all(target_os = "linux", target_env = "gnu"))]
+    fn canceled_unreaped() {
+        use nix::unistd::{close, pipe, write};
+
+        let (rfd, wfd) = pipe().unwrap();
+        let mut rbuf0 = vec![0; 1];
+        let mut rbufs = vec![vec![0; 1]; 4];
+        let mut aior0 = Box::pin(AioRead::new(
+            rfd,
+            0,
+            &mut rbuf0,
+            0,
+            SigevNotify::SigevNone,
+        ));
+        aior0.as_mut().submit().unwrap();
+        let mut aiors = rbufs
+            .iter_mut()
+            .map(|rbuf| {
+                let mut aior = Box::pin(AioRead::new(
+                    rfd,
+                    0,
+                    rbuf,
+                    0,
+                    SigevNotify::SigevNone,
+                ));
+                aior.as_mut().submit().unwrap();
+                assert_eq!(
+                    aior.as_mut().cancel(),
+                    Ok(AioCancelStat::AioCanceled)
+                );
+                assert_eq!(aior.as_mut().state(), AioCbState::Canceled);
+                assert_eq!(aior.as_mut().progress(), AioProgress::Done);
+                assert_eq!(
+                    aior.as_mut().completion_error(),
+                    Some(Errno::ECANCELED)
+                );
+                let snap = aior.as_mut().snapshot();
+                assert_eq!(snap.state, AioCbState::Canceled);
+                assert_eq!(snap.error, Some(Err(Errno::ECANCELED)));
+                aior
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            reap_pending(&mut [aiors[0].as_mut()]),
+            vec![Some(Err(Errno::ECANCELED))]
+        );
+        assert_eq!(aiors[0].as_mut().state(), AioCbState::Reaped);
+
+        assert_eq!(
+            poll_all(&mut [aiors[1].as_mut()]),
+            vec![Err(Errno::ECANCELED)]
+        );
+        assert_eq!(aiors[1].as_mut().state(), AioCbState::Reaped);
+
+        #[cfg(feature = "aio-future")]
+        {
+            use std::{
+                future::Future,
+                sync::Arc,
+                task::{Context, Poll, Wake},
+            };
+
+            struct NoopWaker;
+            impl Wake for NoopWaker {
+                fn wake(self: Arc<Self>) {}
+            }
+
+            let waker = Arc::new(NoopWaker).into();
+            let mut cx = Context::from_waker(&waker);
+            let mut fut = AioFuture::new(aiors[2].as_mut());
+            assert_eq!(
+                Pin::new(&mut fut).poll(&mut cx),
+                Poll::Ready(Err(Errno::ECANCELED))
+            );
+        }
+        #[cfg(not(feature = "aio-future"))]
+        aiors[2].as_mut().aio_return().unwrap_err();
+        assert_eq!(aiors[2].as_mut().state(), AioCbState::Reaped);
+
+        assert_eq!(aiors[3].as_mut().reap(), Ok(AioOutcome::Canceled));
+        assert_eq!(aiors[3].as_mut().state(), AioCbState::Reaped);
+        drop(aiors);
+
+        write(wfd, b"x").unwrap();
+        assert_eq!(poll_aio!(&mut aior0), Ok(()));
+        assert_eq!(aior0.as_mut().aio_return(), Ok(1));
+        close(rfd).unwrap();
+        close(wfd).unwrap();
+    }
+
     /// `AioRead::submit` should not modify the `AioCb` object if
     /// `libc::aio_read` returns an error
     // Skip on Linux, because Linux's AIO implementation can't detect errors
@@ -203,6 +406,212 @@ mod aio_read {
         assert_eq!(EXPECT, rbuf.deref().deref());
     }
 
+    // Use Aio::wait instead of polling
+    #[test]
+    #[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
+    // On Cirrus, aio_suspend is failing with EINVAL
+    // https://github.com/nix-rust/nix/issues/1361
+    #[cfg_attr(target_os = "macos", ignore)]
+    fn wait() {
+        const INITIAL: &[u8] = b"abcdef123456";
+        let mut rbuf = vec![0; 4];
+        const EXPECT: &[u8] = b"cdef";
+        let mut f = tempfile().unwrap();
+        f.write_all(INITIAL).unwrap();
+        {
+            let mut aior = Box::pin(AioRead::new(
+                f.as_raw_fd(),
+                2, //offset
+                &mut rbuf,
+                0, //priority
+                SigevNotify::SigevNone,
+            ));
+            aior.as_mut().submit().unwrap();
+            loop {
+                match aior.wait(Some(TimeSpec::seconds(10))) {
+                    Err(Errno::EINTR) => continue,
+                    r => break r.unwrap(),
+                }
+            }
+            // wait doesn't reap the operation
+            assert!(aior.in_progress());
+            assert_eq!(aior.as_mut().aio_return().unwrap(), EXPECT.len());
+        }
+        assert_eq!(rbuf, EXPECT);
+    }
+
+    #[test]
+    #[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
+    fn bytes_read() {
+        const INITIAL: &[u8] = b"abcdef123456";
+        let mut rbuf = vec![0; 20];
+        let mut f = tempfile().unwrap();
+        f.write_all(INITIAL).unwrap();
+        let mut aior = Box::pin(AioRead::new(
+            f.as_raw_fd(),
+            2,
+            &mut rbuf,
+            0,
+            SigevNotify::SigevNone,
+        ));
+        aior.as_mut().submit().unwrap();
+        poll_aio!(&mut aior).unwrap();
+        assert_eq!(aior.as_mut().bytes_read().unwrap(), INITIAL.len() - 2);
+    }
+
+    // try_into_buffer should hand back the AioRead while it's in progress, and
+    // the buffer once it has been reaped.
+    #[test]
+    #[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
+    fn try_into_buffer() {
+        const INITIAL: &[u8] = b"abcdef123456";
+        let mut rbuf = vec![0; 4];
+        const EXPECT: &[u8] = b"cdef";
+        let mut f = tempfile().unwrap();
+        f.write_all(INITIAL).unwrap();
+        let mut aior = Box::pin(AioRead::new(
+            f.as_raw_fd(),
+            2,
+            &mut rbuf,
+            0,
+            SigevNotify::SigevNone,
+        ));
+        aior.as_mut().submit().unwrap();
+
+        let mut aior = aior.try_into_buffer().unwrap_err();
+        assert!(aior.as_mut().in_progress());
+        assert_eq!(4, aior.nbytes());
+        poll_aio!(&mut aior).unwrap();
+        assert_eq!(aior.as_mut().aio_return().unwrap(), EXPECT.len());
+        let buf = aior.try_into_buffer().unwrap();
+        assert_eq!(EXPECT, buf);
+    }
+
+    #[test]
+    #[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
+    fn data() {
+        const INITIAL: &[u8] = b"abcdef123456";
+        let mut rbuf = vec![0; 4];
+        let mut f = tempfile().unwrap();
+        f.write_all(INITIAL).unwrap();
+        let mut aior = Box::pin(AioRead::new(
+            f.as_raw_fd(),
+            2, //offset
+            &mut rbuf,
+            0, //priority
+            SigevNotify::SigevNone,
+        ));
+        assert_eq!(aior.data(), Some(&[0u8; 4][..]));
+        aior.as_mut().submit().unwrap();
+        assert_eq!(aior.data(), None);
+        poll_aio!(&mut aior).unwrap();
+        aior.as_mut().aio_return().unwrap();
+        assert_eq!(aior.data(), Some(&b"cdef"[..]));
+    }
+
+    // Bytes beyond a short read should be zero, not stale data
+    #[test]
+    #[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
+    fn new_zeroed() {
+        const INITIAL: &[u8] = b"abcdef123456";
+        let mut rbuf = vec![0xff; 8];
+        let mut f = tempfile().unwrap();
+        f.write_all(INITIAL).unwrap();
+        {
+            let mut aior = Box::pin(AioRead::new_zeroed(
+                f.as_raw_fd(),
+                10, //offset
+                &mut rbuf,
+                0, //priority
+                SigevNotify::SigevNone,
+            ));
+            aior.as_mut().submit().unwrap();
+            poll_aio!(&mut aior).unwrap();
+            assert_eq!(aior.as_mut().aio_return().unwrap(), 2);
+        }
+        assert_eq!(rbuf, b"56\0\0\0\0\0\0");
+    }
+
+    // A read that runs into EOF should report the bytes it didn't read
+    #[test]
+    #[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
+    fn remaining() {
+        const INITIAL: &[u8] = b"abcdef123456";
+        let mut rbuf = vec![0; 8];
+        let mut f = tempfile().unwrap();
+        f.write_all(INITIAL).unwrap();
+        let mut aior = Box::pin(AioRead::new(
+            f.as_raw_fd(),
+            10, //offset
+            &mut rbuf,
+            0, //priority
+            SigevNotify::SigevNone,
+        ));
+        aior.as_mut().submit().unwrap();
+        poll_aio!(&mut aior).unwrap();
+        assert_eq!(aior.as_mut().remaining(), Ok(6));
+    }
+
+    #[test]
+    #[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
+    fn finish() {
+        const INITIAL: &[u8] = b"abcdef123456";
+        let mut rbuf = vec![0; 16];
+        let mut f = tempfile().unwrap();
+        f.write_all(INITIAL).unwrap();
+        let mut aior = Box::pin(AioRead::new(
+            f.as_raw_fd(),
+            8, //offset
+            &mut rbuf,
+            0, //priority
+            SigevNotify::SigevNone,
+        ));
+        aior.as_mut().submit().unwrap();
+        poll_aio!(&mut aior).unwrap();
+        // Reading past EOF is short, and the buffer is truncated to match
+        let (len, buf) = aior.finish().unwrap();
+        assert_eq!(len, 4);
+        assert_eq!(buf, b"3456");
+    }
+
+    // Transform the data that was read, then reuse the AioRead to read into
+    // part of the same buffer
+    #[test]
+    #[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
+    fn map_buffer() {
+        const INITIAL: &[u8] = b"abcdef123456";
+        let mut rbuf = vec![0; 8];
+        let mut f = tempfile().unwrap();
+        f.write_all(INITIAL).unwrap();
+        {
+            let mut aior = Box::pin(AioRead::new(
+                f.as_raw_fd(),
+                2, //offset
+                &mut rbuf,
+                0, //priority
+                SigevNotify::SigevNone,
+            ));
+            aior.as_mut().submit().unwrap();
+            poll_aio!(&mut aior).unwrap();
+            assert_eq!(aior.as_mut().aio_return().unwrap(), 8);
+
+            let mut p = std::ptr::null();
+            aior.as_mut().map_buffer(|buf| {
+                buf.make_ascii_uppercase();
+                let rest = &mut buf[4..];
+                p = rest.as_ptr();
+                rest
+            });
+            assert_eq!(aior.nbytes(), 4);
+            let a: &libc::aiocb = (*aior).as_ref();
+            assert_eq!(a.aio_buf as *const u8, p);
+            aior.as_mut().submit().unwrap();
+            poll_aio!(&mut aior).unwrap();
+            assert_eq!(aior.as_mut().aio_return().unwrap(), 4);
+        }
+        assert_eq!(rbuf, b"CDEFcdef");
+    }
+
     // Like ok, but allocates the structure on the stack.
     #[test]
     #[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
@@ -377,6 +786,35 @@ mod aio_write {
         assert_eq!(rbuf, EXPECT);
     }
 
+    #[test]
+    #[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
+    fn from_str() {
+        const INITIAL: &[u8] = b"abcdef123456";
+        let wbuf = "CDEF".to_string();
+        let mut rbuf = String::new();
+        const EXPECT: &str = "abCDEF123456";
+
+        let mut f = tempfile().unwrap();
+        f.write_all(INITIAL).unwrap();
+        let mut aiow = Box::pin(AioWrite::from_str(
+            f.as_raw_fd(),
+            2,
+            &wbuf,
+            0,
+            SigevNotify::SigevNone,
+        ));
+        assert_eq!(wbuf.len(), aiow.nbytes());
+        aiow.as_mut().submit().unwrap();
+
+        let err = poll_aio!(&mut aiow);
+        assert_eq!(err, Ok(()));
+        assert_eq!(aiow.as_mut().aio_return().unwrap(), wbuf.len());
+
+        f.seek(SeekFrom::Start(0)).unwrap();
+        f.read_to_string(&mut rbuf).unwrap();
+        assert_eq!(rbuf, EXPECT);
+    }
+
     // Like ok, but allocates the structure on the stack.
     #[test]
     #[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
@@ -408,41 +846,534 @@ mod aio_write {
         assert_eq!(rbuf, EXPECT);
     }
 
-    /// `AioWrite::write` should not modify the `AioCb` object if
-    /// `libc::aio_write` returns an error.
-    // Skip on Linux, because Linux's AIO implementation can't detect errors
-    // synchronously
     #[test]
-    #[cfg(any(target_os = "freebsd", target_os = "macos"))]
-    fn error() {
-        let wbuf = "CDEF".to_string().into_bytes();
+    #[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
+    fn data() {
+        const WBUF: &[u8] = b"abcd";
+        let f = tempfile().unwrap();
         let mut aiow = Box::pin(AioWrite::new(
-            666, // An invalid file descriptor
-            0,   //offset
-            &wbuf,
+            f.as_raw_fd(),
+            0, //offset
+            WBUF,
             0, //priority
             SigevNotify::SigevNone,
         ));
-        aiow.as_mut().submit().expect_err("assertion failed");
-        // Dropping the AioWrite at this point should not panic
+        assert_eq!(aiow.data(), WBUF);
+        aiow.as_mut().submit().unwrap();
+        assert_eq!(aiow.data(), WBUF);
+        poll_aio!(&mut aiow).unwrap();
+        aiow.as_mut().aio_return().unwrap();
     }
-}
 
-#[cfg(target_os = "freebsd")]
-#[cfg(fbsd14)]
-mod aio_writev {
-    use std::io::IoSlice;
+    // checked_submit should catch a file descriptor that isn't open.
+    #[test]
+    fn checked_submit_closed() {
+        const WBUF: &[u8] = b"abcd";
+        // Don't close a real file to get a bad fd, because a concurrent test
+        // could reuse its number.
+        let mut aiow = Box::pin(AioWrite::new(
+            1001,
+            0, //offset
+            WBUF,
+            0, //priority
+            SigevNotify::SigevNone,
+        ));
+        assert_eq!(aiow.as_mut().checked_submit(), Err(Errno::EBADF));
+        assert!(!aiow.in_progress());
+    }
 
-    use super::*;
+    #[test]
+    #[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
+    fn finish() {
+        const WBUF: &[u8] = b"abcd";
+        let f = tempfile().unwrap();
+        let mut aiow = Box::pin(AioWrite::new(
+            f.as_raw_fd(),
+            0, //offset
+            WBUF,
+            0, //priority
+            SigevNotify::SigevNone,
+        ));
+        aiow.as_mut().submit().unwrap();
+        poll_aio!(&mut aiow).unwrap();
+        let (len, buf) = aiow.finish().unwrap();
+        assert_eq!(len, WBUF.len());
+        assert_eq!(buf, WBUF);
+    }
 
+    // Swap in an uppercased copy of the buffer, and write that instead
     #[test]
-    fn test_accessors() {
-        let wbuf0 = vec![0; 4];
-        let wbuf1 = vec![0; 8];
-        let wbufs = [IoSlice::new(&wbuf0), IoSlice::new(&wbuf1)];
-        let aiocb = AioWritev::new(
-            1001,
-            2, //offset
+    #[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
+    fn map_buffer() {
+        const WBUF: &[u8] = b"abcd";
+        let upper = WBUF.to_ascii_uppercase();
+        let mut rbuf = Vec::new();
+
+        let mut f = tempfile().unwrap();
+        let mut aiow = Box::pin(AioWrite::new(
+            f.as_raw_fd(),
+            0, //offset
+            WBUF,
+            0, //priority
+            SigevNotify::SigevNone,
+        ));
+        aiow.as_mut().map_buffer(|_| &upper[..3]);
+        assert_eq!(aiow.nbytes(), 3);
+        let a: &libc::aiocb = (*aiow).as_ref();
+        assert_eq!(a.aio_buf as *const u8, upper.as_ptr());
+        aiow.as_mut().submit().unwrap();
+        poll_aio!(&mut aiow).unwrap();
+        assert_eq!(aiow.as_mut().aio_return().unwrap(), 3);
+
+        f.seek(SeekFrom::Start(0)).unwrap();
+        f.read_to_end(&mut rbuf).unwrap();
+        assert_eq!(rbuf, b"ABC");
+    }
+
+    #[test]
+    #[should_panic(expected = "in-progress")]
+    #[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
+    fn map_buffer_in_progress() {
+        const WBUF: &[u8] = b"abcd";
+        let f = tempfile().unwrap();
+        let mut aiow = Box::pin(AioWrite::new(
+            f.as_raw_fd(),
+            0, //offset
+            WBUF,
+            0, //priority
+            SigevNotify::SigevNone,
+        ));
+        aiow.as_mut().submit().unwrap();
+        let r = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            aiow.as_mut().map_buffer(|buf| &buf[..2]);
+        }));
+        // Reap the operation before propagating the panic, so the AioWrite
+        // can be dropped safely
+        poll_aio!(&mut aiow).unwrap();
+        aiow.as_mut().aio_return().unwrap();
+        if let Err(e) = r {
+            std::panic::resume_unwind(e);
+        }
+    }
+
+    #[test]
+    #[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
+    fn bytes_written() {
+        let wbuf = b"CDEF";
+        let f = tempfile().unwrap();
+        let mut aiow = Box::pin(AioWrite::new(
+            f.as_raw_fd(),
+            2,
+            wbuf,
+            0,
+            SigevNotify::SigevNone,
+        ));
+        aiow.as_mut().submit().unwrap();
+        poll_aio!(&mut aiow).unwrap();
+        assert_eq!(aiow.as_mut().bytes_written().unwrap(), wbuf.len());
+    }
+
+    // A write to a read-only file descriptor should report EBADF as its
+    // completion error.
+    // Only on Linux, because other platforms detect the error synchronously
+    #[test]
+    #[cfg(target_os = "linux")]
+    #[cfg_attr(target_env = "musl", ignore)]
+    fn completion_error() {
+        let wbuf = b"CDEF";
+        let f = std::fs::File::open("/dev/null").unwrap();
+        let mut aiow = Box::pin(AioWrite::new(
+            f.as_raw_fd(),
+            0,
+            wbuf,
+            0,
+            SigevNotify::SigevNone,
+        ));
+        assert_eq!(None, aiow.as_mut().completion_error());
+        aiow.as_mut().submit().unwrap();
+        let _ = poll_aio!(&mut aiow);
+        assert_eq!(Some(Errno::EBADF), aiow.as_mut().completion_error());
+        // aio_return's own errno need not match the operation's error
+        aiow.as_mut().aio_return().unwrap_err();
+    }
+
+    // The same notification settings may be reused for several operations
+    #[test]
+    fn with_sigev_notify() {
+        let wbuf = vec![0; 4];
+        let sev = SigevNotify::SigevSignal {
+            signal:   Signal::SIGUSR2,
+            si_value: 99,
+        };
+        let aiocbs = [
+            AioWrite::new(1001, 0, &wbuf, 0, SigevNotify::SigevNone)
+                .with_sigev_notify(sev),
+            AioWrite::new(1001, 4, &wbuf, 0, SigevNotify::SigevNone)
+                .with_sigev_notify(sev),
+        ];
+        for aiocb in aiocbs.iter() {
+            let sev = aiocb.sigevent().sigevent();
+            assert_eq!(Signal::SIGUSR2 as i32, sev.sigev_signo);
+            assert_eq!(99, sev.sigev_value.sival_ptr as i64);
+        }
+    }
+
+    #[test]
+    #[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
+    fn snapshot() {
+        const WBUF: &[u8] = b"CDEF";
+        let f = tempfile().unwrap();
+        let mut aiow = Box::pin(AioWrite::new(
+            f.as_raw_fd(),
+            2, //offset
+            WBUF,
+            0, //priority
+            SigevNotify::SigevNone,
+        ));
+        let snap = aiow.as_mut().snapshot();
+        assert_eq!(snap.fd, f.as_raw_fd());
+        assert_eq!(snap.offset, 2);
+        assert_eq!(snap.nbytes, WBUF.len());
+        assert!(!snap.is_read);
+        assert!(snap.is_write);
+        assert_eq!(snap.priority, 0);
+        assert_eq!(snap.state, AioCbState::NotSubmitted);
+        assert_eq!(snap.error, None);
+
+        aiow.as_mut().submit().unwrap();
+        poll_aio!(&mut aiow).unwrap();
+        let done = aiow.as_mut().snapshot();
+        assert_eq!(done.state, AioCbState::Completed);
+        assert_eq!(done.error, Some(Ok(())));
+        assert_eq!(done.offset, snap.offset);
+        assert_eq!(done.nbytes, snap.nbytes);
+
+        aiow.as_mut().aio_return().unwrap();
+        let reaped = aiow.as_mut().snapshot();
+        assert_eq!(reaped.state, AioCbState::Reaped);
+        assert_eq!(reaped.error, None);
+    }
+
+    // Derive two operations from one template
+    #[test]
+    fn clone_config_with() {
+        let wbuf0 = [0; 4];
+        let wbuf1 = [1; 8];
+        let wbuf2 = [2; 16];
+        let sev = SigevNotify::SigevSignal {
+            signal:   Signal::SIGUSR2,
+            si_value: 42,
+        };
+        let template = AioWrite::new(1001, 512, &wbuf0, 1, sev);
+        let aiows = [
+            template.clone_config_with(1002, &wbuf1),
+            template.clone_config_with(1003, &wbuf2),
+        ];
+        for (aiow, (fd, len)) in aiows.iter().zip([(1002, 8), (1003, 16)]) {
+            assert_eq!(aiow.fd(), fd);
+            assert_eq!(aiow.nbytes(), len);
+            assert_eq!(aiow.offset(), template.offset());
+            assert_eq!(aiow.priority(), template.priority());
+            assert_eq!(aiow.op(), AioOp::Write);
+            assert_eq!(aiow.sigevent(), template.sigevent());
+            assert!(!aiow.in_progress());
+        }
+    }
+
+    #[test]
+    fn raw_sigevent() {
+        use nix::sys::signal::SigEvent;
+
+        let wbuf = vec![0; 4];
+        let aiocb = AioWrite::new(
+            1001,
+            0, //offset
+            &wbuf,
+            0, //priority
+            SigevNotify::SigevSignal {
+                signal:   Signal::SIGUSR2,
+                si_value: 99,
+            },
+        );
+        let raw = aiocb.raw_sigevent();
+        assert_eq!(SigEvent::from(raw), aiocb.sigevent());
+        assert_eq!(raw.sigev_signo, Signal::SIGUSR2 as i32);
+        assert_eq!(raw.sigev_value.sival_ptr as i64, 99);
+    }
+
+    #[test]
+    fn notify_kind() {
+        let wbuf = vec![0; 4];
+        let mut aiocb =
+            AioWrite::new(1001, 0, &wbuf, 0, SigevNotify::SigevNone);
+        assert_eq!(SigevNotifyKind::SigevNone, aiocb.notify_kind());
+        aiocb.set_sigev_notify(SigevNotify::SigevSignal {
+            signal:   Signal::SIGUSR2,
+            si_value: 0,
+        });
+        assert_eq!(SigevNotifyKind::SigevSignal, aiocb.notify_kind());
+    }
+
+    #[test]
+    fn new_aligned() {
+        #[repr(align(512))]
+        struct Aligned([u8; 1024]);
+        let wbuf = Aligned([0; 1024]);
+        let aiocb = AioWrite::new_aligned(
+            1001,
+            512, //offset
+            &wbuf.0,
+            0, //priority
+            SigevNotify::SigevNone,
+            512,
+        );
+        assert_eq!(1024, aiocb.nbytes());
+        assert_eq!(512, aiocb.offset());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "offset is misaligned")]
+    fn new_aligned_misaligned() {
+        #[repr(align(512))]
+        struct Aligned([u8; 512]);
+        let wbuf = Aligned([0; 512]);
+        let _ = AioWrite::new_aligned(
+            1001,
+            7, //offset
+            &wbuf.0,
+            0, //priority
+            SigevNotify::SigevNone,
+            512,
+        );
+    }
+
+    // Walk an operation through every stage of its lifecycle
+    #[test]
+    #[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
+    fn state() {
+        let wbuf = "CDEF".to_string().into_bytes();
+        let f = tempfile().unwrap();
+        let mut aiow = Box::pin(AioWrite::new(
+            f.as_raw_fd(),
+            0, //offset
+            &wbuf,
+            0, //priority
+            SigevNotify::SigevNone,
+        ));
+        assert_eq!(aiow.as_mut().state(), AioCbState::NotSubmitted);
+        aiow.as_mut().submit().unwrap();
+        let state = aiow.as_mut().state();
+        assert!(
+            state == AioCbState::InFlight || state == AioCbState::Completed
+        );
+        poll_aio!(&mut aiow).unwrap();
+        assert_eq!(aiow.as_mut().state(), AioCbState::Completed);
+        assert_eq!(aiow.as_mut().aio_return().unwrap(), wbuf.len());
+        assert_eq!(aiow.as_mut().state(), AioCbState::Reaped);
+    }
+
+    #[test]
+    #[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
+    fn is_reaped() {
+        const WBUF: &[u8] = b"abcd";
+        let f = tempfile().unwrap();
+        let mut aiow = Box::pin(AioWrite::new(
+            f.as_raw_fd(),
+            0, //offset
+            WBUF,
+            0, //priority
+            SigevNotify::SigevNone,
+        ));
+        assert!(!aiow.is_reaped());
+        aiow.as_mut().submit().unwrap();
+        poll_aio!(&mut aiow).unwrap();
+        assert!(!aiow.is_reaped());
+        aiow.as_mut().aio_return().unwrap();
+        assert!(aiow.is_reaped());
+        // Resubmitting starts over
+        aiow.as_mut().submit().unwrap();
+        assert!(!aiow.is_reaped());
+        poll_aio!(&mut aiow).unwrap();
+        aiow.as_mut().aio_return().unwrap();
+        assert!(aiow.is_reaped());
+    }
+
+    /// `AioWrite::write` should not modify the `AioCb` object if
+    /// `libc::aio_write` returns an error.
+    // Skip on Linux, because Linux's AIO implementation can't detect errors
+    // synchronously
+    #[test]
+    #[cfg(any(target_os = "freebsd", target_os = "macos"))]
+    fn error() {
+        let wbuf = "CDEF".to_string().into_bytes();
+        let mut aiow = Box::pin(AioWrite::new(
+            666, // An invalid file descriptor
+            0,   //offset
+            &wbuf,
+            0, //priority
+            SigevNotify::SigevNone,
+        ));
+        aiow.as_mut().submit().expect_err("assertion failed");
+        // Dropping the AioWrite at this point should not panic
+    }
+}
+
+mod aio_read_owned {
+    use super::*;
+
+    #[test]
+    #[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
+    fn ok() {
+        const INITIAL: &[u8] = b"abcdef123456";
+        let mut f = tempfile().unwrap();
+        f.write_all(INITIAL).unwrap();
+        let mut aior = Box::pin(AioReadOwned::from_boxed_slice(
+            f.as_raw_fd(),
+            2, //offset
+            vec![0; 4].into_boxed_slice(),
+            0, //priority
+            SigevNotify::SigevNone,
+        ));
+        aior.as_mut().submit().unwrap();
+        assert_eq!(aior.data(), None);
+        // Can't take the buffer back while the kernel may be using it
+        let mut aior = aior.try_into_buf().unwrap_err();
+        assert_eq!(poll_aio!(&mut aior), Ok(()));
+        assert_eq!(aior.as_mut().aio_return(), Ok(4));
+        assert_eq!(aior.data(), Some(&b"cdef"[..]));
+        assert_eq!(&aior.try_into_buf().unwrap()[..], b"cdef");
+    }
+
+    // Dropping a completed operation should free its buffer
+    #[test]
+    #[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
+    fn drop_reaped() {
+        let f = tempfile().unwrap();
+        let mut aior = Box::pin(AioReadOwned::from_vec(
+            f.as_raw_fd(),
+            0, //offset
+            vec![0; 4],
+            0, //priority
+            SigevNotify::SigevNone,
+        ));
+        aior.as_mut().submit().unwrap();
+        assert_eq!(poll_aio!(&mut aior), Ok(()));
+        assert_eq!(aior.as_mut().aio_return(), Ok(0));
+    }
+
+    // lio_listio doesn't mark the operation as in progress, but the buffer
+    // must still be withheld until the read completes.
+    #[test]
+    #[cfg(all(target_os = "linux", target_env = "gnu"))]
+    fn listio() {
+        use nix::unistd::{close, pipe, write};
+
+        let (rfd, wfd) = pipe().unwrap();
+        let mut aior = Box::pin(AioReadOwned::from_vec(
+            rfd,
+            0, //offset
+            vec![0; 1],
+            0, //priority
+            SigevNotify::SigevNone,
+        ));
+        lio_listio(
+            LioMode::LIO_NOWAIT,
+            &mut [aior.as_mut() as Pin<&mut dyn AsMut<libc::aiocb>>],
+            SigevNotify::SigevNone,
+        )
+        .unwrap();
+        assert_eq!(aior.data(), None);
+        let mut aior = aior.try_into_buf().unwrap_err();
+
+        write(wfd, b"x").unwrap();
+        assert_eq!(poll_aio!(&mut aior), Ok(()));
+        assert_eq!(aior.data(), Some(&b"x"[..]));
+        assert_eq!(aior.as_mut().aio_return(), Ok(1));
+        assert_eq!(&aior.try_into_buf().unwrap()[..], b"x");
+        close(rfd).unwrap();
+        close(wfd).unwrap();
+    }
+}
+
+mod aio_write_owned {
+    use super::*;
+
+    #[test]
+    #[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
+    fn ok() {
+        const INITIAL: &[u8] = b"abcdef123456";
+        let mut f = tempfile().unwrap();
+        f.write_all(INITIAL).unwrap();
+        let mut aiow = Box::pin(AioWriteOwned::from_vec(
+            f.as_raw_fd(),
+            2, //offset
+            b"CDEF".to_vec(),
+            0, //priority
+            SigevNotify::SigevNone,
+        ));
+        assert_eq!(aiow.data(), b"CDEF");
+        aiow.as_mut().submit().unwrap();
+        assert_eq!(poll_aio!(&mut aiow), Ok(()));
+        assert_eq!(aiow.as_mut().aio_return(), Ok(4));
+        assert_eq!(&aiow.try_into_buf().unwrap()[..], b"CDEF");
+
+        let mut rbuf = Vec::new();
+        f.seek(SeekFrom::Start(0)).unwrap();
+        f.read_to_end(&mut rbuf).unwrap();
+        assert_eq!(rbuf, b"abCDEF123456");
+    }
+
+    // A shared buffer may be written by several operations at once
+    #[test]
+    #[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
+    fn shared() {
+        use std::sync::Arc;
+
+        let wbuf: Arc<[u8]> = Arc::from(&b"abcd"[..]);
+        let mut f = tempfile().unwrap();
+        let mut aiocbs = (0..2)
+            .map(|i| {
+                Box::pin(AioWriteOwned::new(
+                    f.as_raw_fd(),
+                    i * 4, //offset
+                    wbuf.clone(),
+                    0, //priority
+                    SigevNotify::SigevNone,
+                ))
+            })
+            .collect::<Vec<_>>();
+        for aiow in aiocbs.iter_mut() {
+            aiow.as_mut().submit().unwrap();
+        }
+        for aiow in aiocbs.iter_mut() {
+            assert_eq!(poll_aio!(aiow), Ok(()));
+            assert_eq!(aiow.as_mut().aio_return(), Ok(4));
+        }
+        drop(aiocbs);
+        assert_eq!(Arc::strong_count(&wbuf), 1);
+
+        let mut rbuf = Vec::new();
+        f.read_to_end(&mut rbuf).unwrap();
+        assert_eq!(rbuf, b"abcdabcd");
+    }
+}
+
+#[cfg(target_os = "freebsd")]
+#[cfg(fbsd14)]
+mod aio_writev {
+    use std::io::IoSlice;
+
+    use super::*;
+
+    #[test]
+    fn test_accessors() {
+        let wbuf0 = vec![0; 4];
+        let wbuf1 = vec![0; 8];
+        let wbufs = [IoSlice::new(&wbuf0), IoSlice::new(&wbuf1)];
+        let aiocb = AioWritev::new(
+            1001,
+            2, //offset
             &wbufs,
             42, //priority
             SigevNotify::SigevSignal {
@@ -494,28 +1425,301 @@ mod aio_writev {
     }
 }
 
-// Test an aio operation with completion delivered by a signal
-#[test]
-#[cfg_attr(
-    any(
-        all(target_env = "musl", target_arch = "x86_64"),
-        target_arch = "mips",
-        target_arch = "mips64"
-    ),
-    ignore
-)]
-fn sigev_signal() {
-    let _m = crate::SIGNAL_MTX.lock();
-    let sa = SigAction::new(
-        SigHandler::Handler(sigfunc),
-        SaFlags::SA_RESETHAND,
-        SigSet::empty(),
-    );
-    SIGNALED.store(false, Ordering::Relaxed);
-    unsafe { sigaction(Signal::SIGUSR2, &sa) }.unwrap();
+mod aio_tracker {
+    use super::*;
 
-    const INITIAL: &[u8] = b"abcdef123456";
-    const WBUF: &[u8] = b"CDEF";
+    #[test]
+    #[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
+    fn overlapping_writes() {
+        let wbuf0 = b"abcd";
+        let wbuf1 = b"efgh";
+        let f = tempfile().unwrap();
+        let mut tracker = AioTracker::default();
+        let mut aiow0 = Box::pin(AioWrite::new(
+            f.as_raw_fd(),
+            0, //offset
+            wbuf0,
+            0, //priority
+            SigevNotify::SigevNone,
+        ));
+        let mut aiow1 = Box::pin(AioWrite::new(
+            f.as_raw_fd(),
+            3, //offset
+            wbuf1,
+            0, //priority
+            SigevNotify::SigevNone,
+        ));
+        let mut rbuf = vec![0; 4];
+        let mut aior = Box::pin(AioRead::new(
+            f.as_raw_fd(),
+            4, //offset
+            &mut rbuf,
+            0, //priority
+            SigevNotify::SigevNone,
+        ));
+        let _g0 = tracker.submit(aiow0.as_mut()).unwrap();
+        assert_eq!(tracker.submit(aiow1.as_mut()).unwrap_err(), Errno::EBUSY);
+        assert!(!aiow1.in_progress());
+        // Adjacent, but not overlapping
+        let _gr = tracker.submit(aior.as_mut()).unwrap();
+
+        poll_aio!(&mut aiow0).unwrap();
+        assert_eq!(tracker.aio_return(aiow0.as_mut()).unwrap(), wbuf0.len());
+        poll_aio!(&mut aior).unwrap();
+        tracker.aio_return(aior.as_mut()).unwrap();
+
+        // Now that the first write is done, the second may proceed
+        let _g1 = tracker.submit(aiow1.as_mut()).unwrap();
+        poll_aio!(&mut aiow1).unwrap();
+        assert_eq!(tracker.aio_return(aiow1.as_mut()).unwrap(), wbuf1.len());
+    }
+
+    // An operation reaped without the tracker is forgotten once its guard is
+    // dropped.
+    #[test]
+    #[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
+    fn guard() {
+        let wbuf = b"abcd";
+        let f = tempfile().unwrap();
+        let mut tracker = AioTracker::default();
+        let mut aiow0 = Box::pin(AioWrite::new(
+            f.as_raw_fd(),
+            0, //offset
+            wbuf,
+            0, //priority
+            SigevNotify::SigevNone,
+        ));
+        let mut aiow1 = Box::pin(AioWrite::new(
+            f.as_raw_fd(),
+            0, //offset
+            wbuf,
+            0, //priority
+            SigevNotify::SigevNone,
+        ));
+        let g0 = tracker.submit(aiow0.as_mut()).unwrap();
+        poll_aio!(&mut aiow0).unwrap();
+        assert_eq!(aiow0.as_mut().aio_return().unwrap(), wbuf.len());
+        assert_eq!(tracker.submit(aiow1.as_mut()).unwrap_err(), Errno::EBUSY);
+
+        drop(g0);
+        let _g1 = tracker.submit(aiow1.as_mut()).unwrap();
+        poll_aio!(&mut aiow1).unwrap();
+        assert_eq!(tracker.aio_return(aiow1.as_mut()).unwrap(), wbuf.len());
+    }
+}
+
+mod lio_listio_partial {
+    use super::*;
+
+    #[test]
+    #[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
+    fn ok() {
+        const WBUF: &[u8] = b"abcdef123456";
+        let f = tempfile().unwrap();
+        let mut aiow = Box::pin(AioWrite::new(
+            f.as_raw_fd(),
+            2, //offset
+            WBUF,
+            0, //priority
+            SigevNotify::SigevNone,
+        ));
+        lio_listio_partial(
+            LioMode::LIO_WAIT,
+            &mut [aiow.as_mut()],
+            SigevNotify::SigevNone,
+        )
+        .unwrap();
+        assert_eq!(aiow.as_mut().aio_return().unwrap(), WBUF.len());
+    }
+
+    /// Exceed the per-process AIO queue limit, and check that the accepted
+    /// operations are counted correctly.
+    #[test]
+    #[cfg(target_os = "freebsd")]
+    fn eagain() {
+        use sysctl::{CtlValue, Sysctl};
+
+        let ctl = sysctl::Ctl::new("vfs.aio.max_aio_queue_per_proc").unwrap();
+        let limit = match ctl.value().unwrap() {
+            CtlValue::Int(x) => x as usize,
+            x => panic!("unexpected sysctl value {:?}", x),
+        };
+        const WBUF: &[u8] = b"abcdef123456";
+        let f = tempfile().unwrap();
+        let mut aiocbs = (0..limit + 1)
+            .map(|_| {
+                Box::pin(AioWrite::new(
+                    f.as_raw_fd(),
+                    0, //offset
+                    WBUF,
+                    0, //priority
+                    SigevNotify::SigevNone,
+                ))
+            })
+            .collect::<Vec<_>>();
+        let mut list = aiocbs
+            .iter_mut()
+            .map(|aiocb| aiocb.as_mut() as Pin<&mut dyn AsMut<libc::aiocb>>)
+            .collect::<Vec<_>>();
+        let r = lio_listio_partial(
+            LioMode::LIO_NOWAIT,
+            &mut list[..],
+            SigevNotify::SigevNone,
+        );
+        drop(list);
+        let e = r.unwrap_err();
+        assert_eq!(e.errno, Errno::EAGAIN);
+        assert!(e.accepted <= limit);
+        let mut accepted = 0;
+        for aiocb in aiocbs.iter_mut() {
+            let err = poll_aio!(aiocb);
+            if err != Err(Errno::EAGAIN) {
+                accepted += 1;
+                let _ = aiocb.as_mut().aio_return();
+            }
+        }
+        assert_eq!(accepted, e.accepted);
+    }
+
+    /// glibc refuses to enqueue an operation with an invalid priority, but
+    /// still runs the others.  Only the ones that ran should be counted.
+    #[test]
+    #[cfg(all(target_os = "linux", target_env = "gnu"))]
+    fn rejected() {
+        const WBUF: &[u8] = b"abcdef123456";
+        let f = tempfile().unwrap();
+        let mut aiow0 = Box::pin(AioWrite::new(
+            f.as_raw_fd(),
+            0, //offset
+            WBUF,
+            0, //priority
+            SigevNotify::SigevNone,
+        ));
+        let mut aiow1 = Box::pin(AioWrite::new(
+            f.as_raw_fd(),
+            0, //offset
+            WBUF,
+            0, //priority
+            SigevNotify::SigevNone,
+        ));
+        // Bypass the constructor's validation
+        let aiocb: &mut libc::aiocb =
+            unsafe { aiow1.as_mut().get_unchecked_mut() }.as_mut();
+        aiocb.aio_reqprio = libc::c_int::MAX;
+        let e = lio_listio_partial(
+            LioMode::LIO_WAIT,
+            &mut [aiow0.as_mut(), aiow1.as_mut()],
+            SigevNotify::SigevNone,
+        )
+        .unwrap_err();
+        assert_eq!(e.errno, Errno::EIO);
+        assert_eq!(e.accepted, 1);
+        assert_eq!(aiow0.as_mut().aio_return().unwrap(), WBUF.len());
+    }
+}
+
+mod submit_with_retry {
+    use super::*;
+
+    #[test]
+    #[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
+    fn ok() {
+        const WBUF: &[u8] = b"abcdef123456";
+        let f = tempfile().unwrap();
+        let mut aiow = Box::pin(AioWrite::new(
+            f.as_raw_fd(),
+            0, //offset
+            WBUF,
+            0, //priority
+            SigevNotify::SigevNone,
+        ));
+        aiow.as_mut()
+            .submit_with_retry(3, time::Duration::from_millis(1))
+            .unwrap();
+        assert_eq!(poll_aio!(&mut aiow), Ok(()));
+        assert_eq!(aiow.as_mut().aio_return().unwrap(), WBUF.len());
+    }
+
+    /// Fill the per-process AIO queue, then check that a retried submission
+    /// succeeds once another thread drains it.
+    #[test]
+    #[cfg(target_os = "freebsd")]
+    fn eagain() {
+        use sysctl::{CtlValue, Sysctl};
+
+        let ctl = sysctl::Ctl::new("vfs.aio.max_aio_queue_per_proc").unwrap();
+        let limit = match ctl.value().unwrap() {
+            CtlValue::Int(x) => x as usize,
+            x => panic!("unexpected sysctl value {:?}", x),
+        };
+        const WBUF: &[u8] = b"abcdef123456";
+        let f = tempfile().unwrap();
+        let fd = f.as_raw_fd();
+        let mut aiocbs = Vec::with_capacity(limit);
+        loop {
+            let mut aiocb = Box::pin(AioWrite::new(
+                fd,
+                0, //offset
+                WBUF,
+                0, //priority
+                SigevNotify::SigevNone,
+            ));
+            match aiocb.as_mut().submit() {
+                Ok(()) => aiocbs.push(aiocb),
+                Err(Errno::EAGAIN) => break,
+                Err(e) => panic!("submit failed: {:?}", e),
+            }
+        }
+        let mut aiow = Box::pin(AioWrite::new(
+            fd,
+            0, //offset
+            WBUF,
+            0, //priority
+            SigevNotify::SigevNone,
+        ));
+        assert_eq!(
+            aiow.as_mut().submit_with_retry(0, time::Duration::from_millis(1)),
+            Err(Errno::EAGAIN)
+        );
+
+        let drainer = thread::spawn(move || {
+            thread::sleep(time::Duration::from_millis(50));
+            for aiocb in aiocbs.iter_mut() {
+                poll_aio!(aiocb).unwrap();
+                aiocb.as_mut().aio_return().unwrap();
+            }
+        });
+        aiow.as_mut()
+            .submit_with_retry(1000, time::Duration::from_millis(10))
+            .unwrap();
+        assert_eq!(poll_aio!(&mut aiow), Ok(()));
+        assert_eq!(aiow.as_mut().aio_return().unwrap(), WBUF.len());
+        drainer.join().unwrap();
+    }
+}
+
+// Test an aio operation with completion delivered by a signal
+#[test]
+#[cfg_attr(
+    any(
+        all(target_env = "musl", target_arch = "x86_64"),
+        target_arch = "mips",
+        target_arch = "mips64"
+    ),
+    ignore
+)]
+fn sigev_signal() {
+    let _m = crate::SIGNAL_MTX.lock();
+    let sa = SigAction::new(
+        SigHandler::Handler(sigfunc),
+        SaFlags::SA_RESETHAND,
+        SigSet::empty(),
+    );
+    SIGNALED.store(false, Ordering::Relaxed);
+    unsafe { sigaction(Signal::SIGUSR2, &sa) }.unwrap();
+
+    const INITIAL: &[u8] = b"abcdef123456";
+    const WBUF: &[u8] = b"CDEF";
     let mut rbuf = Vec::new();
     const EXPECT: &[u8] = b"abCDEF123456";
 
@@ -543,6 +1747,238 @@ fn sigev_signal() {
     assert_eq!(rbuf, EXPECT);
 }
 
+static SIGNAL_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+extern "C" fn sigcount(_: c_int) {
+    SIGNAL_COUNT.fetch_add(1, Ordering::Relaxed);
+}
+
+// Submit a batch of operations that share one SigEvent
+#[test]
+#[cfg_attr(
+    any(
+        all(target_env = "musl", target_arch = "x86_64"),
+        target_arch = "mips",
+        target_arch = "mips64"
+    ),
+    ignore
+)]
+fn shared_sigevent() {
+    use nix::sys::signal::SigEvent;
+
+    const WBUF: &[u8] = b"abcd";
+    let _m = crate::SIGNAL_MTX.lock();
+    let sa = SigAction::new(
+        SigHandler::Handler(sigcount),
+        SaFlags::empty(),
+        SigSet::empty(),
+    );
+    SIGNAL_COUNT.store(0, Ordering::Relaxed);
+    let oldsa = unsafe { sigaction(Signal::SIGUSR2, &sa) }.unwrap();
+
+    let sigevent = SigEvent::new(SigevNotify::SigevSignal {
+        signal:   Signal::SIGUSR2,
+        si_value: 0,
+    });
+    let f = tempfile().unwrap();
+    let mut aiocbs = (0..3)
+        .map(|i| {
+            Box::pin(
+                AioWrite::new(
+                    f.as_raw_fd(),
+                    i * 4, //offset
+                    WBUF,
+                    0, //priority
+                    SigevNotify::SigevNone,
+                )
+                .with_sigevent(&sigevent),
+            )
+        })
+        .collect::<Vec<_>>();
+    for (i, aiocb) in aiocbs.iter_mut().enumerate() {
+        assert_eq!(aiocb.notify_kind(), SigevNotifyKind::SigevSignal);
+        aiocb.as_mut().submit().unwrap();
+        // A signal that arrives while another is still pending is merged with
+        // it, so wait for each operation's signal before submitting the next.
+        while SIGNAL_COUNT.load(Ordering::Relaxed) <= i {
+            thread::sleep(time::Duration::from_millis(10));
+        }
+        assert_eq!(aiocb.as_mut().aio_return().unwrap(), WBUF.len());
+    }
+    unsafe { sigaction(Signal::SIGUSR2, &oldsa) }.unwrap();
+}
+
+// Tests pending_count with one completed and one possibly in-flight op.
+#[test]
+#[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
+fn test_pending_count() {
+    let wbuf: &[u8] = b"CDEF";
+
+    let f = tempfile().unwrap();
+    let g = tempfile().unwrap();
+    let mut done = Box::pin(AioWrite::new(
+        f.as_raw_fd(),
+        0, //offset
+        wbuf,
+        0, //priority
+        SigevNotify::SigevNone,
+    ));
+    done.as_mut().submit().unwrap();
+    poll_aio!(&mut done).unwrap();
+    let mut inflight = Box::pin(AioWrite::new(
+        f.as_raw_fd(),
+        4, //offset
+        wbuf,
+        0, //priority
+        SigevNotify::SigevNone,
+    ));
+    inflight.as_mut().submit().unwrap();
+
+    let pending = pending_count(f.as_raw_fd(), &[&*done, &*inflight]);
+    assert!(pending <= 1);
+    assert_eq!(0, pending_count(g.as_raw_fd(), &[&*done, &*inflight]));
+    poll_aio!(&mut inflight).unwrap();
+    assert_eq!(0, pending_count(f.as_raw_fd(), &[&*done, &*inflight]));
+
+    done.as_mut().aio_return().unwrap();
+    inflight.as_mut().aio_return().unwrap();
+}
+
+#[test]
+fn test_priority_range() {
+    let range = priority_range();
+    assert!(!range.is_empty());
+    assert_eq!(0, *range.start());
+}
+
+// Where the system reports no limit, nonzero priorities must still be
+// submitted
+#[test]
+#[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
+fn test_aio_priority_unlimited() {
+    use crate::*;
+
+    const WBUF: &[u8] = b"abcd";
+
+    if aio_prio_delta_max().is_some() {
+        skip!("The system reports AIO_PRIO_DELTA_MAX.  Skipping test.");
+    }
+    assert_eq!(libc::c_int::MAX, *priority_range().end());
+    let f = tempfile().unwrap();
+    let mut aiow = Box::pin(AioWrite::new(
+        f.as_raw_fd(),
+        0, //offset
+        WBUF,
+        1, //priority
+        SigevNotify::SigevNone,
+    ));
+    aiow.as_mut().submit().unwrap();
+    poll_aio!(&mut aiow).unwrap();
+    assert_eq!(aiow.as_mut().aio_return().unwrap(), WBUF.len());
+}
+
+// glibc always reports AIO_PRIO_DELTA_MAX
+#[test]
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+fn test_aio_prio_delta_max() {
+    let max = aio_prio_delta_max().unwrap();
+    assert!(max > 0);
+    assert_eq!(max, *priority_range().end());
+}
+
+// Out-of-range priorities should be rejected before reaching the kernel
+#[test]
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+fn test_aio_priority_out_of_range() {
+    let f = tempfile().unwrap();
+    let max = aio_prio_delta_max().unwrap();
+    for prio in [-1, max + 1] {
+        let mut aiow = Box::pin(AioWrite::new(
+            f.as_raw_fd(),
+            0,
+            b"abcd",
+            prio,
+            SigevNotify::SigevNone,
+        ));
+        assert_eq!(aiow.as_mut().submit(), Err(Errno::EINVAL));
+        assert!(!aiow.in_progress());
+
+        let mut aiof = Box::pin(AioFsync::new(
+            f.as_raw_fd(),
+            AioFsyncMode::O_SYNC,
+            prio,
+            SigevNotify::SigevNone,
+        ));
+        assert_eq!(aiof.as_mut().submit(), Err(Errno::EINVAL));
+        assert!(!aiof.in_progress());
+    }
+}
+
+#[test]
+fn test_aio_cancel_stat_into_result() {
+    assert_eq!(Ok(()), AioCancelStat::AioCanceled.into_result());
+    assert_eq!(Ok(()), AioCancelStat::AioAllDone.into_result());
+    assert_eq!(
+        Err(AioCancelStat::AioNotCanceled),
+        AioCancelStat::AioNotCanceled.into_result()
+    );
+}
+
+// lio_listio in LIO_NOWAIT mode should deliver its notification once the
+// whole batch is complete.
+#[test]
+#[cfg_attr(
+    any(
+        all(target_env = "musl", target_arch = "x86_64"),
+        target_arch = "mips",
+        target_arch = "mips64"
+    ),
+    ignore
+)]
+fn test_lio_listio_nowait_signal() {
+    let _m = crate::SIGNAL_MTX.lock();
+    let sa = SigAction::new(
+        SigHandler::Handler(sigfunc),
+        SaFlags::SA_RESETHAND,
+        SigSet::empty(),
+    );
+    SIGNALED.store(false, Ordering::Relaxed);
+    unsafe { sigaction(Signal::SIGUSR2, &sa) }.unwrap();
+
+    const WBUF: &[u8] = b"CDEF";
+    let f = tempfile().unwrap();
+    let mut aiow0 = Box::pin(AioWrite::new(
+        f.as_raw_fd(),
+        0, //offset
+        WBUF,
+        0, //priority
+        SigevNotify::SigevNone,
+    ));
+    let mut aiow1 = Box::pin(AioWrite::new(
+        f.as_raw_fd(),
+        4, //offset
+        WBUF,
+        0, //priority
+        SigevNotify::SigevNone,
+    ));
+    let sev = SigevNotify::SigevSignal {
+        signal:   Signal::SIGUSR2,
+        si_value: 0,
+    };
+    lio_listio(
+        LioMode::LIO_NOWAIT,
+        &mut [aiow0.as_mut(), aiow1.as_mut()],
+        sev,
+    )
+    .unwrap();
+    while !SIGNALED.load(Ordering::Relaxed) {
+        thread::sleep(time::Duration::from_millis(10));
+    }
+
+    assert_eq!(aiow0.as_mut().aio_return().unwrap(), WBUF.len());
+    assert_eq!(aiow1.as_mut().aio_return().unwrap(), WBUF.len());
+}
+
 // Tests using aio_cancel_all for all outstanding IOs.
 #[test]
 #[cfg_attr(target_env = "musl", ignore)]
@@ -568,14 +2004,387 @@ fn test_aio_cancel_all() {
     let _ = aiocb.as_mut().aio_return();
 }
 
+// Tests aio_cancel_all_with_status with one completed and one in-flight op.
 #[test]
-// On Cirrus on Linux, this test fails due to a glibc bug.
-// https://github.com/nix-rust/nix/issues/1099
-#[cfg_attr(target_os = "linux", ignore)]
-// On Cirrus, aio_suspend is failing with EINVAL
-// https://github.com/nix-rust/nix/issues/1361
-#[cfg_attr(target_os = "macos", ignore)]
-fn test_aio_suspend() {
+#[cfg_attr(target_env = "musl", ignore)]
+fn test_aio_cancel_all_with_status() {
+    let wbuf: &[u8] = b"CDEF";
+
+    let f = tempfile().unwrap();
+    let mut done = Box::pin(AioWrite::new(
+        f.as_raw_fd(),
+        0, //offset
+        wbuf,
+        0, //priority
+        SigevNotify::SigevNone,
+    ));
+    done.as_mut().submit().unwrap();
+    poll_aio!(&mut done).unwrap();
+    let mut inflight = Box::pin(AioWrite::new(
+        f.as_raw_fd(),
+        4, //offset
+        wbuf,
+        0, //priority
+        SigevNotify::SigevNone,
+    ));
+    inflight.as_mut().submit().unwrap();
+
+    let (_, status) = aio_cancel_all_with_status(
+        f.as_raw_fd(),
+        &mut [done.as_mut(), inflight.as_mut()],
+    )
+    .unwrap();
+    assert_eq!(2, status.len());
+    assert_eq!(Ok(()), status[0]);
+    assert!(!done.in_progress());
+    assert!(
+        status[1] == Ok(())
+            || status[1] == Err(Errno::EINPROGRESS)
+            || status[1] == Err(Errno::ECANCELED)
+    );
+    // Only an operation that is still running remains in progress
+    assert_eq!(
+        inflight.in_progress(),
+        status[1] == Err(Errno::EINPROGRESS)
+    );
+
+    assert_eq!(done.as_mut().aio_return().unwrap(), wbuf.len());
+    // Wait for inflight to complete, but don't care whether it succeeded
+    let _ = poll_aio!(&mut inflight);
+    let _ = inflight.as_mut().aio_return();
+}
+
+/// An unused `lio_listio` slot
+struct Nop(libc::aiocb);
+
+impl Nop {
+    fn new() -> Self {
+        let mut aiocb = unsafe { std::mem::zeroed::<libc::aiocb>() };
+        aiocb.aio_lio_opcode = libc::LIO_NOP;
+        Nop(aiocb)
+    }
+}
+
+impl AsMut<libc::aiocb> for Nop {
+    fn as_mut(&mut self) -> &mut libc::aiocb {
+        &mut self.0
+    }
+}
+
+// lio_listio should skip LIO_NOP entries, and the batch helpers should ignore
+// them.
+#[test]
+#[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
+fn test_lio_listio_nop() {
+    const INITIAL: &[u8] = b"abcdef123456";
+    const WBUF: &[u8] = b"CDEF";
+    let mut rbuf = vec![0; 4];
+    let mut f = tempfile().unwrap();
+    f.write_all(INITIAL).unwrap();
+    {
+        let mut aior = Box::pin(AioRead::new(
+            f.as_raw_fd(),
+            8, //offset
+            &mut rbuf,
+            0, //priority
+            SigevNotify::SigevNone,
+        ));
+        let mut aiow = Box::pin(AioWrite::new(
+            f.as_raw_fd(),
+            2, //offset
+            WBUF,
+            0, //priority
+            SigevNotify::SigevNone,
+        ));
+        let mut nop0 = Nop::new();
+        let mut nop1 = Nop::new();
+        {
+            let mut list = [
+                Pin::new(&mut nop0) as Pin<&mut dyn AsMut<libc::aiocb>>,
+                aior.as_mut(),
+                Pin::new(&mut nop1) as Pin<&mut dyn AsMut<libc::aiocb>>,
+                aiow.as_mut(),
+            ];
+            lio_listio(
+                LioMode::LIO_WAIT,
+                &mut list[..],
+                SigevNotify::SigevNone,
+            )
+            .unwrap();
+        }
+        // An unused slot of a batch of writes
+        let mut nop = AioWrite::new(
+            f.as_raw_fd(),
+            0, //offset
+            WBUF,
+            0, //priority
+            SigevNotify::SigevNone,
+        );
+        nop.as_mut().aio_lio_opcode = libc::LIO_NOP;
+        let mut nop = Box::pin(nop);
+        let (_, status) = aio_cancel_all_with_status(
+            f.as_raw_fd(),
+            &mut [
+                nop.as_mut() as Pin<&mut dyn Aio<Output = usize>>,
+                aior.as_mut(),
+                aiow.as_mut(),
+            ],
+        )
+        .unwrap();
+        assert_eq!(status, vec![Ok(()), Ok(()), Ok(())]);
+
+        assert_eq!(aior.as_mut().aio_return().unwrap(), 4);
+        assert_eq!(aiow.as_mut().aio_return().unwrap(), WBUF.len());
+    }
+    assert_eq!(rbuf, b"3456");
+}
+
+// Submit an owned batch without waiting, and poll each entry for completion
+#[test]
+#[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
+fn test_liocb() {
+    const INITIAL: &[u8] = b"abcdef123456";
+    const WBUF: &[u8] = b"CDEF";
+    let mut rbuf = vec![0; 4];
+    let mut f = tempfile().unwrap();
+    f.write_all(INITIAL).unwrap();
+    {
+        let mut liocb = Box::new(
+            LioCbBuilder::with_capacity(2)
+                .emplace_read(
+                    f.as_raw_fd(),
+                    8, //offset
+                    &mut rbuf,
+                    0, //priority
+                    SigevNotify::SigevNone,
+                )
+                .emplace_write(
+                    f.as_raw_fd(),
+                    2, //offset
+                    WBUF,
+                    0, //priority
+                    SigevNotify::SigevNone,
+                )
+                .finish(),
+        );
+        assert_eq!(liocb.len(), 2);
+        liocb
+            .listio(LioMode::LIO_NOWAIT, SigevNotify::SigevNone)
+            .unwrap();
+        for i in 0..liocb.len() {
+            while liocb.error(i) == Err(Errno::EINPROGRESS) {
+                thread::sleep(time::Duration::from_millis(10));
+            }
+        }
+        assert_eq!(liocb.aio_return(0), Ok(4));
+        assert_eq!(liocb.aio_return(1), Ok(WBUF.len()));
+    }
+    assert_eq!(rbuf, b"3456");
+
+    let mut rbuf = Vec::new();
+    f.seek(SeekFrom::Start(0)).unwrap();
+    f.read_to_end(&mut rbuf).unwrap();
+    assert_eq!(rbuf, b"abCDEF123456");
+}
+
+// An operation that fails after being accepted should be reported, not
+// resubmitted.
+// glibc reports the bad file descriptor asynchronously.
+#[test]
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+fn test_liocb_resubmit() {
+    use nix::unistd::{close, pipe};
+
+    const WBUF: &[u8] = b"abcd";
+    let f = tempfile().unwrap();
+    let (rfd, wfd) = pipe().unwrap();
+    let mut liocb = LioCbBuilder::with_capacity(2)
+        .emplace_write(f.as_raw_fd(), 0, WBUF, 0, SigevNotify::SigevNone)
+        // Writing to the read end of a pipe fails with EBADF
+        .emplace_write(rfd, 0, WBUF, 0, SigevNotify::SigevNone)
+        .finish();
+    assert_eq!(
+        liocb.listio(LioMode::LIO_WAIT, SigevNotify::SigevNone),
+        Err(Errno::EIO)
+    );
+    assert_eq!(
+        liocb.listio_resubmit(LioMode::LIO_WAIT, SigevNotify::SigevNone),
+        Ok(vec![(1, Errno::EBADF)])
+    );
+    assert_eq!(liocb.aio_return(0), Ok(WBUF.len()));
+    assert_eq!(liocb.error(1), Err(Errno::EBADF));
+    liocb.aio_return(1).unwrap_err();
+    close(rfd).unwrap();
+    close(wfd).unwrap();
+}
+
+// An operation that was canceled should be reported, not resubmitted.
+// glibc runs one operation per file descriptor at a time, so the second read
+// stays queued behind the first, which blocks on the empty pipe.
+#[test]
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+fn test_liocb_resubmit_canceled() {
+    use nix::unistd::{close, pipe, write};
+
+    let (rfd, wfd) = pipe().unwrap();
+    let mut rbuf0 = vec![0; 1];
+    let mut rbuf1 = vec![0; 1];
+    let mut liocb = LioCbBuilder::with_capacity(2)
+        .emplace_read(rfd, 0, &mut rbuf0, 0, SigevNotify::SigevNone)
+        .emplace_read(rfd, 0, &mut rbuf1, 0, SigevNotify::SigevNone)
+        .finish();
+    liocb
+        .listio(LioMode::LIO_NOWAIT, SigevNotify::SigevNone)
+        .unwrap();
+    assert_eq!(aio_cancel_all(rfd), Ok(AioCancelStat::AioNotCanceled));
+    assert_eq!(liocb.error(1), Err(Errno::ECANCELED));
+    assert_eq!(
+        liocb.listio_resubmit(LioMode::LIO_NOWAIT, SigevNotify::SigevNone),
+        Ok(vec![(1, Errno::ECANCELED)])
+    );
+    assert_eq!(liocb.error(1), Err(Errno::ECANCELED));
+
+    write(wfd, b"x").unwrap();
+    while liocb.error(0) == Err(Errno::EINPROGRESS) {
+        thread::sleep(time::Duration::from_millis(10));
+    }
+    assert_eq!(liocb.aio_return(0), Ok(1));
+    liocb.aio_return(1).unwrap_err();
+    drop(liocb);
+    assert_eq!(rbuf0, b"x");
+    close(rfd).unwrap();
+    close(wfd).unwrap();
+}
+
+#[test]
+#[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
+fn test_aio_context() {
+    let mut f = tempfile().unwrap();
+    let mut ctx = AioContext::new(f.as_raw_fd());
+    let tokens = [b"abcd", b"efgh", b"ijkl"]
+        .iter()
+        .enumerate()
+        .map(|(i, wbuf)| {
+            let aiow = AioWrite::new(
+                f.as_raw_fd(),
+                i as libc::off_t * 4, //offset
+                &wbuf[..],
+                0, //priority
+                SigevNotify::SigevNone,
+            );
+            ctx.submit(aiow).unwrap()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(ctx.len(), 3);
+    ctx.suspend_all(Some(TimeSpec::seconds(10))).unwrap();
+    let mut results = ctx.drain().collect::<Vec<_>>();
+    results.sort_by_key(|(t, _)| *t);
+    assert_eq!(
+        results,
+        tokens.iter().map(|t| (*t, Ok(4))).collect::<Vec<_>>()
+    );
+    assert!(ctx.is_empty());
+    assert_eq!(ctx.drain().count(), 0);
+
+    let mut rbuf = Vec::new();
+    f.read_to_end(&mut rbuf).unwrap();
+    assert_eq!(rbuf, b"abcdefghijkl");
+}
+
+#[test]
+fn test_aio_context_wrong_fd() {
+    let mut ctx = AioContext::new(1001);
+    let aiow = AioWrite::new(1002, 0, b"abcd", 0, SigevNotify::SigevNone);
+    assert_eq!(ctx.submit(aiow), Err(Errno::EINVAL));
+    assert!(ctx.is_empty());
+}
+
+// Dropping a context with outstanding operations should wait for them rather
+// than panic.
+#[test]
+#[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
+fn test_aio_context_drop() {
+    let mut f = tempfile().unwrap();
+    {
+        let mut ctx = AioContext::new(f.as_raw_fd());
+        let aiow =
+            AioWrite::new(f.as_raw_fd(), 0, b"abcd", 0, SigevNotify::SigevNone);
+        ctx.submit(aiow).unwrap();
+    }
+    let mut rbuf = Vec::new();
+    f.read_to_end(&mut rbuf).unwrap();
+    // The write may or may not have been cancelled before it started
+    assert!(rbuf.is_empty() || rbuf == b"abcd");
+}
+
+// A context cancels only its own operations, not others on the same fd.
+// glibc runs one operation per file descriptor at a time, so the context's
+// reads stay queued behind the first, which blocks on the empty pipe.
+#[test]
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+fn test_aio_context_cancel_all() {
+    use nix::unistd::{close, pipe, write};
+
+    let (rfd, wfd) = pipe().unwrap();
+    let mut rbuf = vec![0; 1];
+    let mut aior = Box::pin(AioRead::new(
+        rfd,
+        0, //offset
+        &mut rbuf,
+        0, //priority
+        SigevNotify::SigevNone,
+    ));
+    aior.as_mut().submit().unwrap();
+    let mut ctxbufs = vec![vec![0; 1]; 2];
+    {
+        let mut ctx = AioContext::new(rfd);
+        let tokens = ctxbufs
+            .iter_mut()
+            .map(|buf| {
+                let aior =
+                    AioRead::new(rfd, 0, buf, 0, SigevNotify::SigevNone);
+                ctx.submit(aior).unwrap()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(ctx.cancel_all(), Ok(AioCancelStat::AioCanceled));
+        assert_eq!(aior.as_mut().state(), AioCbState::InFlight);
+        let mut results = ctx.drain().collect::<Vec<_>>();
+        results.sort_by_key(|(t, _)| *t);
+        let canceled = tokens
+            .iter()
+            .map(|t| (*t, Err(Errno::ECANCELED)))
+            .collect::<Vec<_>>();
+        assert_eq!(results, canceled);
+    }
+
+    write(wfd, b"x").unwrap();
+    assert_eq!(poll_aio!(&mut aior), Ok(()));
+    assert_eq!(aior.as_mut().aio_return(), Ok(1));
+    drop(aior);
+    assert_eq!(rbuf, b"x");
+    close(rfd).unwrap();
+    close(wfd).unwrap();
+}
+
+// Dropping a batch with unreaped operations is a resource leak
+#[test]
+#[should_panic(expected = "Dropped an in-progress AioCb")]
+#[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
+fn test_liocb_drop_unreaped() {
+    let f = tempfile().unwrap();
+    let mut liocb = LioCbBuilder::with_capacity(1)
+        .emplace_write(f.as_raw_fd(), 0, b"abcd", 0, SigevNotify::SigevNone)
+        .finish();
+    liocb
+        .listio(LioMode::LIO_WAIT, SigevNotify::SigevNone)
+        .unwrap();
+}
+
+#[test]
+// On Cirrus, aio_suspend is failing with EINVAL
+// https://github.com/nix-rust/nix/issues/1361
+#[cfg_attr(target_os = "macos", ignore)]
+fn test_aio_suspend() {
     const INITIAL: &[u8] = b"abcdef123456";
     const WBUF: &[u8] = b"CDEFG";
     let timeout = TimeSpec::seconds(10);
@@ -624,3 +2433,654 @@ fn test_aio_suspend() {
     assert_eq!(wcb.as_mut().aio_return().unwrap(), WBUF.len());
     assert_eq!(rcb.as_mut().aio_return().unwrap(), rlen);
 }
+
+#[test]
+fn test_aio_capabilities() {
+    let caps = AioCapabilities::query();
+
+    #[cfg(any(
+        target_os = "ios",
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "netbsd"
+    ))]
+    {
+        assert!(caps.dsync);
+        // Make sure the variant really exists on this platform
+        let _ = AioFsyncMode::O_DSYNC;
+    }
+    #[cfg(target_os = "freebsd")]
+    {
+        assert!(!caps.dsync);
+        assert!(caps.sigev_kevent);
+        assert!(caps.vectored);
+    }
+    #[cfg(any(target_os = "freebsd", target_os = "linux"))]
+    assert!(caps.sigev_thread_id);
+    #[cfg(not(any(target_os = "freebsd", target_os = "linux")))]
+    assert!(!caps.sigev_thread_id);
+    #[cfg(not(target_os = "freebsd"))]
+    {
+        assert!(!caps.sigev_kevent);
+        assert!(!caps.vectored);
+    }
+}
+
+#[test]
+#[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
+fn test_into_io_result() {
+    const WBUF: &[u8] = b"abcd";
+    let f = tempfile().unwrap();
+    let mut aiow = Box::pin(AioWrite::new(
+        f.as_raw_fd(),
+        0, //offset
+        WBUF,
+        0, //priority
+        SigevNotify::SigevNone,
+    ));
+    aiow.as_mut().submit().unwrap();
+    poll_aio!(&mut aiow).unwrap();
+    let r = into_io_result(aiow.as_mut().aio_return());
+    assert_eq!(r.unwrap(), WBUF.len());
+
+    let e = into_io_result::<usize>(Err(Errno::EBADF)).unwrap_err();
+    assert_eq!(e.raw_os_error(), Some(libc::EBADF));
+    assert_eq!(e.kind(), std::io::Error::from_raw_os_error(libc::EBADF).kind());
+}
+
+#[test]
+fn test_aio_builder() {
+    let builder = AioBuilder::new(1001)
+        .offset(2)
+        .priority(42)
+        .sigev_notify(SigevNotify::SigevSignal {
+            signal: Signal::SIGUSR2,
+            si_value: 99,
+        });
+
+    let mut rbuf = vec![0; 4];
+    let aior = builder.read(&mut rbuf);
+    assert_eq!(1001, aior.fd());
+    assert_eq!(2, aior.offset());
+    assert_eq!(4, aior.nbytes());
+    assert_eq!(42, aior.priority());
+    assert_eq!(Signal::SIGUSR2 as i32, aior.sigevent().sigevent().sigev_signo);
+    assert!(aior.is_read());
+
+    let aiow = builder.fd(1002).write(b"abcdef");
+    assert_eq!(1002, aiow.fd());
+    assert_eq!(2, aiow.offset());
+    assert_eq!(6, aiow.nbytes());
+    assert!(aiow.is_write());
+
+    let aiof = builder.fsync(AioFsyncMode::O_SYNC);
+    assert_eq!(1001, aiof.fd());
+    assert_eq!(AioFsyncMode::O_SYNC, aiof.mode());
+    assert_eq!(42, aiof.priority());
+}
+
+// Ids are distinct, and survive moving the operation before it's pinned
+#[test]
+fn test_aio_id() {
+    let mut rbuf = vec![0; 4];
+    let aior = AioRead::new(1001, 0, &mut rbuf, 0, SigevNotify::SigevNone);
+    let aiow = AioWrite::new(1001, 0, b"abcd", 0, SigevNotify::SigevNone);
+    assert_ne!(aior.id(), aiow.id());
+
+    let id = aiow.id();
+    let aiow = Box::pin(aiow);
+    assert_eq!(aiow.id(), id);
+}
+
+// Submit one operation of each kind through the generic Aio interface
+#[test]
+#[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
+fn test_aio_op() {
+    fn run<T: Aio + ?Sized>(mut aiocb: Pin<&mut T>) {
+        let op = aiocb.op();
+        aiocb.as_mut().submit().unwrap();
+        // Submission must not change the reported operation
+        assert_eq!(aiocb.op(), op);
+        assert_eq!(poll_aio!(&mut aiocb), Ok(()));
+        aiocb.as_mut().aio_return().unwrap();
+    }
+
+    const INITIAL: &[u8] = b"abcdef123456";
+    let mut f = tempfile().unwrap();
+    f.write_all(INITIAL).unwrap();
+    let mut rbuf = vec![0; 4];
+    let mut aior = Box::pin(AioRead::new(
+        f.as_raw_fd(),
+        2, //offset
+        &mut rbuf,
+        0, //priority
+        SigevNotify::SigevNone,
+    ));
+    let mut aiow = Box::pin(AioWrite::new(
+        f.as_raw_fd(),
+        0, //offset
+        b"AB",
+        0, //priority
+        SigevNotify::SigevNone,
+    ));
+    let mut aiof = Box::pin(AioFsync::new(
+        f.as_raw_fd(),
+        AioFsyncMode::O_SYNC,
+        0, //priority
+        SigevNotify::SigevNone,
+    ));
+
+    assert_eq!(aior.op(), AioOp::Read);
+    assert_eq!(aiow.op(), AioOp::Write);
+    assert_eq!(aiof.op(), AioOp::Fsync(AioFsyncMode::O_SYNC));
+
+    run(aior.as_mut());
+    run(aiow.as_mut());
+    run(aiof.as_mut());
+    drop(aior);
+    assert_eq!(rbuf, b"cdef");
+}
+
+// aio_suspend_uninterrupted should keep waiting after a signal, but only for
+// the remainder of the original timeout.
+#[test]
+// glibc implements AIO on pipes with threads; other platforms may not support
+// it at all.
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+fn test_aio_suspend_uninterrupted() {
+    use nix::{
+        sys::pthread::{pthread_kill, pthread_self},
+        unistd::{pipe, write},
+    };
+
+    let _m = crate::SIGNAL_MTX.lock();
+    let sa = SigAction::new(
+        SigHandler::Handler(sigfunc),
+        SaFlags::SA_RESETHAND,
+        SigSet::empty(),
+    );
+    SIGNALED.store(false, Ordering::Relaxed);
+    unsafe { sigaction(Signal::SIGUSR2, &sa) }.unwrap();
+
+    let (rfd, wfd) = pipe().unwrap();
+    let mut rbuf = vec![0; 1];
+    // Nothing has been written to the pipe, so this won't complete on its own
+    let mut rcb = Box::pin(AioRead::new(
+        rfd,
+        0, //offset
+        &mut rbuf,
+        0, //priority
+        SigevNotify::SigevNone,
+    ));
+    rcb.as_mut().submit().unwrap();
+
+    let me = pthread_self();
+    let killer = thread::spawn(move || {
+        thread::sleep(time::Duration::from_millis(50));
+        pthread_kill(me, Signal::SIGUSR2).unwrap();
+    });
+    let budget = time::Duration::from_millis(250);
+    let start = time::Instant::now();
+    let r = aio_suspend_uninterrupted(&[&*rcb], Some(TimeSpec::from(budget)));
+    let elapsed = start.elapsed();
+    killer.join().unwrap();
+
+    assert_eq!(r, Err(Errno::EAGAIN));
+    assert!(SIGNALED.load(Ordering::Relaxed));
+    assert!(elapsed >= budget, "returned after only {:?}", elapsed);
+    assert!(elapsed < budget * 4, "waited too long: {:?}", elapsed);
+
+    write(wfd, b"x").unwrap();
+    assert_eq!(poll_aio!(&mut rcb), Ok(()));
+    assert_eq!(rcb.as_mut().aio_return().unwrap(), 1);
+    nix::unistd::close(rfd).unwrap();
+    nix::unistd::close(wfd).unwrap();
+}
+
+// Drive a write and then a read to completion through AioFuture
+#[test]
+#[cfg(feature = "aio-future")]
+#[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
+fn test_aio_future() {
+    use std::{
+        future::Future,
+        sync::Arc,
+        task::{Context, Poll, Wake},
+    };
+
+    struct NoopWaker;
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    fn block_on<F: Future + Unpin>(mut fut: F) -> F::Output {
+        let waker = Arc::new(NoopWaker).into();
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            if let Poll::Ready(r) = Pin::new(&mut fut).poll(&mut cx) {
+                break r;
+            }
+            thread::yield_now();
+        }
+    }
+
+    const WBUF: &[u8] = b"abcdef";
+    let f = tempfile().unwrap();
+    let mut aiow = Box::pin(AioWrite::new(
+        f.as_raw_fd(),
+        0, //offset
+        WBUF,
+        0, //priority
+        SigevNotify::SigevNone,
+    ));
+    assert_eq!(block_on(AioFuture::new(aiow.as_mut())), Ok(WBUF.len()));
+    assert!(!aiow.in_progress());
+
+    let mut rbuf = vec![0; 4];
+    {
+        let mut aior = Box::pin(AioRead::new(
+            f.as_raw_fd(),
+            2, //offset
+            &mut rbuf,
+            0, //priority
+            SigevNotify::SigevNone,
+        ));
+        assert_eq!(block_on(AioFuture::new(aior.as_mut())), Ok(4));
+    }
+    assert_eq!(rbuf, b"cdef");
+}
+
+// Only the operation that has completed should be reported
+#[test]
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+fn test_aio_suspend_any() {
+    use nix::unistd::{close, pipe, write};
+
+    const WBUF: &[u8] = b"abcd";
+    let f = tempfile().unwrap();
+    let (rfd, wfd) = pipe().unwrap();
+    let mut rbuf = vec![0; 1];
+    // Nothing has been written to the pipe, so this won't complete on its own
+    let mut aior = Box::pin(AioRead::new(
+        rfd,
+        0, //offset
+        &mut rbuf,
+        0, //priority
+        SigevNotify::SigevNone,
+    ));
+    let mut aiow = Box::pin(AioWrite::new(
+        f.as_raw_fd(),
+        0, //offset
+        WBUF,
+        0, //priority
+        SigevNotify::SigevNone,
+    ));
+    aior.as_mut().submit().unwrap();
+    aiow.as_mut().submit().unwrap();
+    assert_eq!(aio_suspend_any(&[&*aior, &*aiow], None), Ok(vec![1]));
+    assert_eq!(aiow.as_mut().aio_return(), Ok(WBUF.len()));
+
+    write(wfd, b"x").unwrap();
+    assert_eq!(aio_suspend_any(&[&*aior], None), Ok(vec![0]));
+    assert_eq!(aior.as_mut().aio_return(), Ok(1));
+    close(rfd).unwrap();
+    close(wfd).unwrap();
+}
+
+// Each operation should be returned exactly once, in whatever order they
+// complete.
+#[test]
+#[cfg(target_os = "freebsd")]
+fn test_aio_waitcomplete() {
+    const WBUF0: &[u8] = b"abcd";
+    const WBUF1: &[u8] = b"efghij";
+    let f = tempfile().unwrap();
+    let mut aiow0 = Box::pin(AioWrite::new(
+        f.as_raw_fd(),
+        0, //offset
+        WBUF0,
+        0, //priority
+        SigevNotify::SigevNone,
+    ));
+    let mut aiow1 = Box::pin(AioWrite::new(
+        f.as_raw_fd(),
+        4, //offset
+        WBUF1,
+        0, //priority
+        SigevNotify::SigevNone,
+    ));
+    aiow0.as_mut().submit().unwrap();
+    aiow1.as_mut().submit().unwrap();
+
+    let mut list = [aiow0.as_mut(), aiow1.as_mut()];
+    let mut results = [None, None];
+    for _ in 0..2 {
+        let (op, r) = aio_waitcomplete(&mut list[..], None).unwrap();
+        let i = match op {
+            AioCompletion::Listed(i) => i,
+            AioCompletion::Unlisted(p) => panic!("Unexpected aiocb {:?}", p),
+        };
+        assert!(results[i].is_none());
+        results[i] = Some(r);
+    }
+    assert_eq!(results, [Some(Ok(WBUF0.len())), Some(Ok(WBUF1.len()))]);
+    assert!(!aiow0.in_progress());
+    assert!(!aiow1.in_progress());
+    assert!(aiow0.is_reaped());
+    assert!(aiow1.is_reaped());
+}
+
+// An operation missing from the list is identified by its address
+#[test]
+#[cfg(target_os = "freebsd")]
+fn test_aio_waitcomplete_unlisted() {
+    let f = tempfile().unwrap();
+    let mut aiow = Box::pin(AioWrite::new(
+        f.as_raw_fd(),
+        0, //offset
+        b"abcd",
+        0, //priority
+        SigevNotify::SigevNone,
+    ));
+    aiow.as_mut().submit().unwrap();
+    let mut list: [Pin<&mut AioWrite>; 0] = [];
+    let (op, r) = aio_waitcomplete(&mut list[..], None).unwrap();
+    let p: *const libc::aiocb = (*aiow).as_ref();
+    assert_eq!(op, AioCompletion::Unlisted(p as *mut libc::aiocb));
+    assert_eq!(r, Ok(4));
+    // The kernel has already forgotten it
+    aiow.as_mut().aio_return().unwrap_err();
+}
+
+#[test]
+#[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
+fn test_aio_seq_writer() {
+    const CHUNKS: [&[u8]; 3] = [b"abc", b"defg", b"hi"];
+    let mut f = tempfile().unwrap();
+    let mut writer = AioSeqWriter::new(f.as_raw_fd(), 0);
+    let mut aiocbs = CHUNKS
+        .iter()
+        .map(|chunk| writer.write(chunk, 0, SigevNotify::SigevNone).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(writer.offset(), 9);
+    for aiocb in aiocbs.iter_mut() {
+        assert_eq!(poll_aio!(aiocb), Ok(()));
+        let len = aiocb.nbytes();
+        assert_eq!(writer.aio_return(aiocb.as_mut()), Ok(len));
+    }
+    assert_eq!(writer.offset(), 9);
+
+    let mut rbuf = Vec::new();
+    f.seek(SeekFrom::Start(0)).unwrap();
+    f.read_to_end(&mut rbuf).unwrap();
+    assert_eq!(rbuf, b"abcdefghi");
+}
+
+// Reap one operation individually, then reap the whole batch
+#[test]
+#[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
+fn test_reap_pending() {
+    const WBUF: &[u8] = b"abcd";
+    let f = tempfile().unwrap();
+    let mut aiocbs = (0..3)
+        .map(|i| {
+            Box::pin(AioWrite::new(
+                f.as_raw_fd(),
+                i * 4, //offset
+                WBUF,
+                0, //priority
+                SigevNotify::SigevNone,
+            ))
+        })
+        .collect::<Vec<_>>();
+    for aiocb in aiocbs.iter_mut() {
+        aiocb.as_mut().submit().unwrap();
+        assert_eq!(poll_aio!(aiocb), Ok(()));
+    }
+    assert_eq!(aiocbs[1].as_mut().aio_return(), Ok(WBUF.len()));
+
+    let mut list = aiocbs
+        .iter_mut()
+        .map(|aiocb| aiocb.as_mut())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        reap_pending(&mut list[..]),
+        vec![Some(Ok(WBUF.len())), None, Some(Ok(WBUF.len()))]
+    );
+    // Everything has been reaped now
+    assert_eq!(reap_pending(&mut list[..]), vec![None, None, None]);
+}
+
+// The first operation completes last, and the third one fails.  Results should
+// still be reported in submission order.
+// glibc reports the bad file descriptor asynchronously, and processes requests
+// on the same fd one at a time.
+#[test]
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+fn test_poll_all() {
+    use nix::unistd::{close, pipe, write};
+
+    const WBUF: &[u8] = b"abcd";
+    let f = tempfile().unwrap();
+    let (rfd, wfd) = pipe().unwrap();
+    let mut rbuf = vec![0; 1];
+    let mut aior = Box::pin(AioRead::new(
+        rfd,
+        0,
+        &mut rbuf,
+        0,
+        SigevNotify::SigevNone,
+    ));
+    let mut aiow = Box::pin(AioWrite::new(
+        f.as_raw_fd(),
+        0,
+        WBUF,
+        0,
+        SigevNotify::SigevNone,
+    ));
+    // Writing to the read end of a pipe fails with EBADF
+    let mut aiow_bad =
+        Box::pin(AioWrite::new(rfd, 0, WBUF, 0, SigevNotify::SigevNone));
+    let mut aiow_unsubmitted = Box::pin(AioWrite::new(
+        f.as_raw_fd(),
+        0,
+        WBUF,
+        0,
+        SigevNotify::SigevNone,
+    ));
+    aior.as_mut().submit().unwrap();
+    aiow.as_mut().submit().unwrap();
+    assert_eq!(poll_aio!(&mut aiow), Ok(()));
+    aiow_bad.as_mut().submit().unwrap();
+    write(wfd, b"x").unwrap();
+
+    let mut list = [
+        aior.as_mut() as Pin<&mut dyn Aio<Output = usize>>,
+        aiow.as_mut() as Pin<&mut dyn Aio<Output = usize>>,
+        aiow_bad.as_mut() as Pin<&mut dyn Aio<Output = usize>>,
+        aiow_unsubmitted.as_mut() as Pin<&mut dyn Aio<Output = usize>>,
+    ];
+    assert_eq!(
+        poll_all(&mut list[..]),
+        vec![Ok(1), Ok(WBUF.len()), Err(Errno::EBADF), Ok(0)]
+    );
+    assert!(list.iter().all(|aiocb| !aiocb.in_progress()));
+    close(rfd).unwrap();
+    close(wfd).unwrap();
+}
+
+#[test]
+#[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
+fn test_aio_append() {
+    const INITIAL: &[u8] = b"abcd";
+    const CHUNKS: [&[u8]; 2] = [b"efg", b"hijkl"];
+    let mut f = tempfile().unwrap();
+    f.write_all(INITIAL).unwrap();
+    let mut expected_offset = INITIAL.len();
+    for chunk in CHUNKS.iter() {
+        let mut aiow =
+            aio_append(f.as_raw_fd(), chunk, 0, SigevNotify::SigevNone)
+                .unwrap();
+        assert_eq!(aiow.offset() as usize, expected_offset);
+        assert_eq!(poll_aio!(&mut aiow), Ok(()));
+        assert_eq!(aiow.as_mut().aio_return(), Ok(chunk.len()));
+        expected_offset += chunk.len();
+    }
+
+    let mut rbuf = Vec::new();
+    f.seek(SeekFrom::Start(0)).unwrap();
+    f.read_to_end(&mut rbuf).unwrap();
+    assert_eq!(rbuf, b"abcdefghijkl");
+}
+
+// Write a buffer that doesn't divide evenly into chunks, at a nonzero offset
+#[test]
+#[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
+fn test_aio_write_chunked() {
+    const OFFSET: usize = 3;
+    let wbuf = (0..100_000).map(|i| i as u8).collect::<Vec<_>>();
+    let mut f = tempfile().unwrap();
+    let r =
+        aio_write_chunked(f.as_raw_fd(), OFFSET as libc::off_t, &wbuf, 4096, 4);
+    assert_eq!(r, Ok(wbuf.len()));
+
+    let mut rbuf = Vec::new();
+    f.read_to_end(&mut rbuf).unwrap();
+    assert_eq!(rbuf.len(), OFFSET + wbuf.len());
+    assert_eq!(&rbuf[..OFFSET], &[0, 0, 0]);
+    assert_eq!(&rbuf[OFFSET..], &wbuf[..]);
+}
+
+// Writing to a bad file descriptor should fail
+#[test]
+#[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
+fn test_aio_write_chunked_error() {
+    use nix::unistd::close;
+    use std::os::unix::io::IntoRawFd;
+
+    // A file descriptor that was just closed is invalid
+    let fd = tempfile().unwrap().into_raw_fd();
+    close(fd).unwrap();
+    let wbuf = vec![0; 1000];
+    assert_eq!(aio_write_chunked(fd, 0, &wbuf, 100, 4), Err(Errno::EBADF));
+}
+
+// Once a chunk fails, the ones already in flight are still waited for and
+// reaped.  Dropping an unreaped chunk would panic.  A memfd that may not grow
+// accepts the chunks that fit, and rejects the rest.
+#[test]
+#[cfg(target_os = "linux")]
+#[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
+fn test_aio_write_chunked_partial_error() {
+    use nix::fcntl::{fcntl, FcntlArg, SealFlag};
+    use nix::sys::memfd::{memfd_create, MemFdCreateFlag};
+    use nix::unistd::ftruncate;
+    use std::ffi::CString;
+    use std::fs::File;
+    use std::os::unix::io::FromRawFd;
+
+    const SIZE: usize = 300;
+    let name = CString::new("test_aio_write_chunked").unwrap();
+    let fd = memfd_create(&name, MemFdCreateFlag::MFD_ALLOW_SEALING).unwrap();
+    let mut f = unsafe { File::from_raw_fd(fd) };
+    ftruncate(fd, SIZE as libc::off_t).unwrap();
+    fcntl(fd, FcntlArg::F_ADD_SEALS(SealFlag::F_SEAL_GROW)).unwrap();
+
+    let wbuf = vec![42u8; 1000];
+    assert_eq!(aio_write_chunked(fd, 0, &wbuf, 100, 4), Err(Errno::EPERM));
+
+    // The chunks that were in flight alongside the failed one all completed
+    let mut rbuf = Vec::new();
+    f.read_to_end(&mut rbuf).unwrap();
+    assert_eq!(rbuf, &wbuf[..SIZE]);
+}
+
+// Read a file in fixed-size chunks until EOF
+#[test]
+#[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
+fn test_aio_seq_reader() {
+    const INITIAL: &[u8] = b"abcdefghij";
+    let mut f = tempfile().unwrap();
+    f.write_all(INITIAL).unwrap();
+    let mut reader = AioSeqReader::new(f.as_raw_fd(), 0);
+    let mut contents = Vec::new();
+    loop {
+        let mut rbuf = [0; 4];
+        let len = {
+            let mut aior =
+                reader.read(&mut rbuf, 0, SigevNotify::SigevNone).unwrap();
+            assert_eq!(poll_aio!(&mut aior), Ok(()));
+            reader.aio_return(aior.as_mut()).unwrap()
+        };
+        if len == 0 {
+            break;
+        }
+        contents.extend_from_slice(&rbuf[..len]);
+        assert_eq!(reader.offset() as usize, contents.len());
+    }
+    assert_eq!(contents, INITIAL);
+    assert_eq!(reader.offset() as usize, INITIAL.len());
+}
+
+mod aio_executor {
+    use super::*;
+
+    #[test]
+    fn read() {
+        const INITIAL: &[u8] = b"abcdef123456";
+        let mut f = tempfile().unwrap();
+        f.write_all(INITIAL).unwrap();
+        let executor = AioExecutor::new(1);
+        let handle = executor.submit(AioExecutorOp::Read {
+            fd:   f.as_raw_fd(),
+            offs: 2,
+            buf:  vec![0; 4],
+        });
+        let err = handle.poll();
+        assert!(err == Ok(()) || err == Err(Errno::EINPROGRESS));
+        let (r, buf) = handle.reap();
+        assert_eq!(r, Ok(4));
+        assert_eq!(buf, b"cdef");
+    }
+
+    #[test]
+    fn write_and_fsync() {
+        const INITIAL: &[u8] = b"abcdef123456";
+        const EXPECT: &[u8] = b"abCDEF123456";
+        let mut rbuf = Vec::new();
+        let mut f = tempfile().unwrap();
+        f.write_all(INITIAL).unwrap();
+        let executor = AioExecutor::new(2);
+        let handle = executor.submit(AioExecutorOp::Write {
+            fd:   f.as_raw_fd(),
+            offs: 2,
+            buf:  b"CDEF".to_vec(),
+        });
+        let (r, buf) = handle.reap();
+        assert_eq!(r, Ok(4));
+        assert_eq!(buf, b"CDEF");
+
+        let handle = executor.submit(AioExecutorOp::Fsync {
+            fd:   f.as_raw_fd(),
+            mode: AioFsyncMode::O_SYNC,
+        });
+        while handle.poll() == Err(Errno::EINPROGRESS) {
+            thread::sleep(time::Duration::from_millis(10));
+        }
+        assert_eq!(handle.reap().0, Ok(0));
+
+        f.seek(SeekFrom::Start(0)).unwrap();
+        f.read_to_end(&mut rbuf).unwrap();
+        assert_eq!(rbuf, EXPECT);
+    }
+
+    #[test]
+    fn error() {
+        let executor = AioExecutor::new(1);
+        let handle = executor.submit(AioExecutorOp::Fsync {
+            fd:   -1,
+            mode: AioFsyncMode::O_SYNC,
+        });
+        assert_eq!(handle.reap().0, Err(Errno::EBADF));
+    }
+}
@@ -244,6 +244,59 @@ pub fn test_abstract_uds_addr() {
     assert_eq!(unsafe { (*addr.as_ptr()).sun_path[0] }, 0);
 }
 
+// Test Linux's autobind feature, over a SOCK_SEQPACKET socket so that message
+// boundaries are checked too.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+pub fn test_unnamed_uds_autobind() {
+    use nix::sys::socket::{
+        accept, bind, connect, listen, recv, send, socket, MsgFlags, SockFlag,
+        SockType,
+    };
+    use nix::unistd::close;
+
+    let unnamed = UnixAddr::new_unnamed();
+    assert!(unnamed.is_unnamed());
+    assert_eq!(unnamed.path(), None);
+    assert_eq!(unnamed.as_abstract(), None);
+
+    let listener = socket(
+        AddressFamily::Unix,
+        SockType::SeqPacket,
+        SockFlag::empty(),
+        None,
+    )
+    .unwrap();
+    bind(listener, &unnamed).unwrap();
+    listen(listener, 1).unwrap();
+    let addr: UnixAddr = getsockname(listener).unwrap();
+    assert!(!addr.is_unnamed());
+    // The kernel picks a name of five hex digits
+    assert_eq!(addr.as_abstract().unwrap().len(), 5);
+
+    let client = socket(
+        AddressFamily::Unix,
+        SockType::SeqPacket,
+        SockFlag::empty(),
+        None,
+    )
+    .unwrap();
+    connect(client, &addr).unwrap();
+    let server = accept(listener).unwrap();
+
+    send(client, b"hello", MsgFlags::empty()).unwrap();
+    send(client, b"world", MsgFlags::empty()).unwrap();
+    let mut buf = [0u8; 16];
+    assert_eq!(recv(server, &mut buf, MsgFlags::empty()).unwrap(), 5);
+    assert_eq!(&buf[..5], b"hello");
+    assert_eq!(recv(server, &mut buf, MsgFlags::empty()).unwrap(), 5);
+    assert_eq!(&buf[..5], b"world");
+
+    close(server).unwrap();
+    close(client).unwrap();
+    close(listener).unwrap();
+}
+
 #[test]
 pub fn test_getsockname() {
     use nix::sys::socket::bind;
@@ -263,6 +316,72 @@ pub fn test_getsockname() {
     assert_eq!(sockaddr, getsockname(sock).expect("getsockname failed"));
 }
 
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+pub fn test_link_addr() {
+    use nix::sys::socket::{LinkAddr, SockaddrLike};
+
+    let mac = [0x02, 0x00, 0x00, 0x12, 0x34, 0x56];
+    let addr = LinkAddr::new((libc::ETH_P_IP as u16).to_be(), 3, Some(mac));
+    assert_eq!(addr.protocol(), (libc::ETH_P_IP as u16).to_be());
+    assert_eq!(addr.ifindex(), 3);
+    assert_eq!(addr.halen(), 6);
+    assert_eq!(addr.addr(), Some(mac));
+    assert_eq!(addr.to_string(), "02:00:00:12:34:56");
+    assert_eq!(addr.family(), Some(AddressFamily::Packet));
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+pub fn test_packet_bind() {
+    use nix::net::if_::if_nametoindex;
+    use nix::sys::socket::{
+        bind, socket, LinkAddr, SockFlag, SockProtocol, SockType,
+    };
+    use nix::unistd::close;
+
+    require_capability!("test_packet_bind", CAP_NET_RAW);
+
+    let sock = socket(
+        AddressFamily::Packet,
+        SockType::Raw,
+        SockFlag::empty(),
+        SockProtocol::EthAll,
+    )
+    .expect("socket failed");
+    let ifindex = if_nametoindex("lo").unwrap() as usize;
+    let sockaddr = LinkAddr::new((libc::ETH_P_ALL as u16).to_be(), ifindex, None);
+    bind(sock, &sockaddr).expect("bind failed");
+    let bound: LinkAddr = getsockname(sock).expect("getsockname failed");
+    assert_eq!(bound.ifindex(), ifindex);
+    assert_eq!(bound.protocol(), sockaddr.protocol());
+    close(sock).unwrap();
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+pub fn test_netlink_generic() {
+    use nix::sys::socket::{
+        bind, socket, AddressFamily, NetlinkAddr, SockFlag, SockProtocol,
+        SockType,
+    };
+    use nix::unistd::close;
+
+    let sock = socket(
+        AddressFamily::Netlink,
+        SockType::Datagram,
+        SockFlag::empty(),
+        SockProtocol::NetlinkGeneric,
+    )
+    .expect("socket failed");
+    // A port ID of 0 lets the kernel assign one
+    bind(sock, &NetlinkAddr::new(0, 0)).expect("bind failed");
+    let addr: NetlinkAddr = getsockname(sock).expect("getsockname failed");
+    assert_ne!(addr.pid(), 0);
+    assert_eq!(addr.groups(), 0);
+    close(sock).unwrap();
+}
+
 #[test]
 pub fn test_socketpair() {
     use nix::sys::socket::{socketpair, AddressFamily, SockFlag, SockType};
@@ -295,6 +414,83 @@ pub fn test_std_conversions() {
     assert_eq!(std_sa, sock_addr.into());
 }
 
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+#[test]
+pub fn test_peer_credentials() {
+    use nix::sys::socket::{
+        peer_credentials, socketpair, SockFlag, SockType,
+    };
+    use nix::unistd::{getegid, geteuid, getpid};
+
+    let (a, b) = socketpair(
+        AddressFamily::Unix,
+        SockType::Stream,
+        None,
+        SockFlag::empty(),
+    )
+    .unwrap();
+    for fd in [a, b] {
+        let cred = peer_credentials(fd).unwrap();
+        assert_eq!(cred.uid(), geteuid().as_raw());
+        assert_eq!(cred.gid(), getegid().as_raw());
+        if let Some(pid) = cred.pid() {
+            assert_eq!(pid, getpid().as_raw());
+        }
+    }
+    #[cfg(any(
+        target_os = "android",
+        target_os = "ios",
+        target_os = "linux",
+        target_os = "macos"
+    ))]
+    assert!(peer_credentials(a).unwrap().pid().is_some());
+
+    nix::unistd::close(a).unwrap();
+    nix::unistd::close(b).unwrap();
+}
+
+#[test]
+pub fn test_std_conversions_lossless() {
+    use nix::errno::Errno;
+    use nix::sys::socket::*;
+    use std::convert::TryFrom;
+
+    let std_sa = SocketAddrV6::new(net::Ipv6Addr::LOCALHOST, 6000, 0x1234_5678, 3);
+    let sock_addr = SockaddrIn6::from(std_sa);
+    assert_eq!(sock_addr.flowinfo(), 0x1234_5678);
+    assert_eq!(sock_addr.scope_id(), 3);
+    assert_eq!(std_sa, SocketAddrV6::from(sock_addr));
+
+    let std_sa = SocketAddr::V6(std_sa);
+    assert_eq!(std_sa, SocketAddr::from(sock_addr));
+    assert_eq!(sock_addr, SockaddrIn6::try_from(std_sa).unwrap());
+    assert_eq!(Err(Errno::EAFNOSUPPORT), SockaddrIn::try_from(std_sa));
+    let storage = SockaddrStorage::from(std_sa);
+    assert_eq!(Ok(std_sa), SocketAddr::try_from(storage));
+
+    let std_sa = SocketAddr::from_str("127.0.0.1:6789").unwrap();
+    let sock_addr = SockaddrIn::try_from(std_sa).unwrap();
+    assert_eq!(std_sa, SocketAddr::from(sock_addr));
+    assert_eq!(Err(Errno::EAFNOSUPPORT), SockaddrIn6::try_from(std_sa));
+    let storage = SockaddrStorage::from(std_sa);
+    assert_eq!(Ok(std_sa), SocketAddr::try_from(storage));
+
+    let ua = UnixAddr::new("/tmp/sock").unwrap();
+    let storage =
+        unsafe { SockaddrStorage::from_raw(ua.as_ptr().cast(), Some(ua.len())) }
+            .unwrap();
+    assert_eq!(Err(Errno::EAFNOSUPPORT), SocketAddr::try_from(storage));
+}
+
 mod recvfrom {
     use super::*;
     use nix::sys::socket::*;
@@ -377,6 +573,46 @@ mod recvfrom {
         assert_eq!(AddressFamily::Inet, from.unwrap().family().unwrap());
     }
 
+    #[test]
+    pub fn udp_uninit() {
+        use std::mem::MaybeUninit;
+
+        let sock_addr = SockaddrIn::new(127, 0, 0, 1, 0);
+        let rsock = socket(
+            AddressFamily::Inet,
+            SockType::Datagram,
+            SockFlag::empty(),
+            None,
+        )
+        .unwrap();
+        bind(rsock, &sock_addr).unwrap();
+        let raddr = getsockname::<SockaddrIn>(rsock).unwrap();
+        let ssock = socket(
+            AddressFamily::Inet,
+            SockType::Datagram,
+            SockFlag::empty(),
+            None,
+        )
+        .unwrap();
+        bind(ssock, &sock_addr).unwrap();
+        let saddr = getsockname::<SockaddrIn>(ssock).unwrap();
+
+        let mut buf = [MaybeUninit::<u8>::uninit(); 64];
+        sendto(ssock, MSG, &raddr, MsgFlags::empty()).unwrap();
+        let (data, from) =
+            recvfrom_uninit::<SockaddrIn>(rsock, &mut buf).unwrap();
+        assert_eq!(data, MSG);
+        assert_eq!(from, Some(saddr));
+
+        sendto(ssock, MSG, &raddr, MsgFlags::empty()).unwrap();
+        let data =
+            recv_uninit(rsock, &mut buf[..5], MsgFlags::empty()).unwrap();
+        assert_eq!(data, &MSG[..5]);
+
+        nix::unistd::close(rsock).unwrap();
+        nix::unistd::close(ssock).unwrap();
+    }
+
     #[cfg(target_os = "linux")]
     mod udp_offload {
         use super::*;
@@ -744,6 +980,41 @@ pub fn test_recvmsg_ebadf() {
     assert_eq!(r.err().unwrap(), Errno::EBADF);
 }
 
+// A short buffer should be reported with MSG_TRUNC, and peek_datagram_len
+// should report the datagram's full size beforehand.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+pub fn test_recvmsg_trunc() {
+    use nix::sys::socket::{
+        peek_datagram_len, recvmsg, send, socketpair, AddressFamily,
+        MsgFlags, SockFlag, SockType,
+    };
+    use nix::unistd::close;
+    use std::io::IoSliceMut;
+
+    let (fd1, fd2) = socketpair(
+        AddressFamily::Unix,
+        SockType::Datagram,
+        None,
+        SockFlag::empty(),
+    )
+    .unwrap();
+    send(fd1, b"0123456789", MsgFlags::empty()).unwrap();
+
+    assert_eq!(peek_datagram_len(fd2).unwrap(), 10);
+
+    let mut buf = [0u8; 4];
+    let mut iov = [IoSliceMut::new(&mut buf[..])];
+    let msg = recvmsg::<()>(fd2, &mut iov, None, MsgFlags::empty()).unwrap();
+    assert_eq!(msg.bytes, 4);
+    assert!(msg.flags.contains(MsgFlags::MSG_TRUNC));
+    assert!(!msg.flags.contains(MsgFlags::MSG_CTRUNC));
+    assert_eq!(&buf, b"0123");
+
+    close(fd1).unwrap();
+    close(fd2).unwrap();
+}
+
 // Disable the test on emulated platforms due to a bug in QEMU versions <
 // 2.12.0.  https://bugs.launchpad.net/qemu/+bug/1701808
 #[cfg_attr(qemu, ignore)]
@@ -817,6 +1088,75 @@ pub fn test_scm_rights() {
     close(w).unwrap();
 }
 
+#[cfg_attr(qemu, ignore)]
+#[test]
+pub fn test_send_recv_fds() {
+    use nix::fcntl::{fcntl, FcntlArg, FdFlag};
+    use nix::sys::socket::{
+        recv_fds, send_fds, socketpair, AddressFamily, SockFlag, SockType,
+    };
+    use nix::unistd::{close, pipe, read, write};
+
+    let (fd1, fd2) = socketpair(
+        AddressFamily::Unix,
+        SockType::Stream,
+        None,
+        SockFlag::empty(),
+    )
+    .unwrap();
+    let (r, w) = pipe().unwrap();
+    assert_eq!(send_fds(fd1, &[r], b"hello").unwrap(), 5);
+    close(r).unwrap();
+
+    let mut buf = [0u8; 5];
+    let (bytes, fds) = recv_fds(fd2, &mut buf, 1).unwrap();
+    assert_eq!(&buf[..bytes], b"hello");
+    assert_eq!(fds.len(), 1);
+    let received_r = fds[0];
+    let flags = fcntl(received_r, FcntlArg::F_GETFD).unwrap();
+    assert!(FdFlag::from_bits_truncate(flags).contains(FdFlag::FD_CLOEXEC));
+
+    write(w, b"world").unwrap();
+    let mut buf = [0u8; 5];
+    read(received_r, &mut buf).unwrap();
+    assert_eq!(&buf, b"world");
+
+    close(received_r).unwrap();
+    close(w).unwrap();
+    close(fd1).unwrap();
+    close(fd2).unwrap();
+}
+
+// Receiving more file descriptors than requested should be an error, rather
+// than silently dropping some of them.
+#[cfg_attr(qemu, ignore)]
+#[test]
+pub fn test_recv_fds_truncated() {
+    use nix::errno::Errno;
+    use nix::sys::socket::{
+        recv_fds, send_fds, socketpair, AddressFamily, SockFlag, SockType,
+    };
+    use nix::unistd::{close, pipe};
+
+    let (fd1, fd2) = socketpair(
+        AddressFamily::Unix,
+        SockType::Stream,
+        None,
+        SockFlag::empty(),
+    )
+    .unwrap();
+    let (r, w) = pipe().unwrap();
+    send_fds(fd1, &[r, w], b"x").unwrap();
+
+    let mut buf = [0u8; 1];
+    assert_eq!(recv_fds(fd2, &mut buf, 1), Err(Errno::EMSGSIZE));
+
+    close(r).unwrap();
+    close(w).unwrap();
+    close(fd1).unwrap();
+    close(fd2).unwrap();
+}
+
 // Disable the test on emulated platforms due to not enabled support of AF_ALG in QEMU from rust cross
 #[cfg(any(target_os = "linux", target_os = "android"))]
 #[cfg_attr(qemu, ignore)]
@@ -1967,6 +2307,83 @@ pub fn test_recvif_ipv6() {
     }
 }
 
+#[cfg(any(
+    target_os = "android",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+))]
+#[test]
+pub fn test_recv_tos() {
+    use nix::sys::socket::sockopt::{
+        IpRecvTos, IpTos, Ipv6RecvTClass, Ipv6TClass,
+    };
+    use nix::sys::socket::{
+        bind, getsockname, recvmsg, sendto, setsockopt, socket,
+        ControlMessageOwned, MsgFlags, SockFlag, SockType, SockaddrStorage,
+    };
+    use std::io::IoSliceMut;
+
+    // DSCP EF, with ECN bits clear
+    const TOS: u8 = 0xb8;
+
+    for family in [AddressFamily::Inet, AddressFamily::Inet6] {
+        let localhost: SockaddrStorage = match family {
+            AddressFamily::Inet => SocketAddr::from_str("127.0.0.1:0"),
+            _ => SocketAddr::from_str("[::1]:0"),
+        }
+        .unwrap()
+        .into();
+        let receive =
+            socket(family, SockType::Datagram, SockFlag::empty(), None)
+                .unwrap();
+        if bind(receive, &localhost).is_err() {
+            // IPv6 may be unavailable
+            nix::unistd::close(receive).unwrap();
+            continue;
+        }
+        let address: SockaddrStorage = getsockname(receive).unwrap();
+        let send = socket(family, SockType::Datagram, SockFlag::empty(), None)
+            .unwrap();
+        if family == AddressFamily::Inet {
+            setsockopt(receive, IpRecvTos, &true).unwrap();
+            setsockopt(send, IpTos, &TOS.into()).unwrap();
+        } else {
+            setsockopt(receive, Ipv6RecvTClass, &true).unwrap();
+            setsockopt(send, Ipv6TClass, &TOS.into()).unwrap();
+        }
+        sendto(send, b"hi", &address, MsgFlags::empty()).unwrap();
+
+        let mut buf = [0u8; 8];
+        let mut iov = [IoSliceMut::new(&mut buf)];
+        let mut cmsgspace = cmsg_space!(libc::c_int);
+        let msg = recvmsg::<()>(
+            receive,
+            &mut iov,
+            Some(&mut cmsgspace),
+            MsgFlags::empty(),
+        )
+        .unwrap();
+        let mut cmsgs = msg.cmsgs();
+        match cmsgs.next() {
+            Some(ControlMessageOwned::Ipv4Tos(tos)) => {
+                assert_eq!(family, AddressFamily::Inet);
+                assert_eq!(tos, TOS);
+            }
+            Some(ControlMessageOwned::Ipv6TClass(tclass)) => {
+                assert_eq!(family, AddressFamily::Inet6);
+                assert_eq!(tclass, i32::from(TOS));
+            }
+            cmsg => panic!("Unexpected control message {:?}", cmsg),
+        }
+        assert!(cmsgs.next().is_none());
+
+        nix::unistd::close(receive).unwrap();
+        nix::unistd::close(send).unwrap();
+    }
+}
+
 #[cfg(any(
     target_os = "android",
     target_os = "freebsd",
@@ -2117,6 +2534,26 @@ pub fn test_vsock() {
     thr.join().unwrap();
 }
 
+// The re-exported constants should be usable in place of libc's
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+pub fn test_vsock_reexports() {
+    use nix::sys::socket::{
+        VsockAddr, VMADDR_CID_ANY, VMADDR_CID_HOST, VMADDR_CID_HYPERVISOR,
+        VMADDR_CID_LOCAL, VMADDR_PORT_ANY,
+    };
+
+    assert_eq!(VMADDR_CID_ANY, libc::VMADDR_CID_ANY);
+    assert_eq!(VMADDR_CID_HOST, libc::VMADDR_CID_HOST);
+    assert_eq!(VMADDR_CID_HYPERVISOR, libc::VMADDR_CID_HYPERVISOR);
+    assert_eq!(VMADDR_CID_LOCAL, libc::VMADDR_CID_LOCAL);
+    assert_eq!(VMADDR_PORT_ANY, libc::VMADDR_PORT_ANY);
+
+    let addr = VsockAddr::new(VMADDR_CID_HOST, VMADDR_PORT_ANY);
+    assert_eq!(addr.cid(), libc::VMADDR_CID_HOST);
+    assert_eq!(addr.port(), libc::VMADDR_PORT_ANY);
+}
+
 // Disable the test on emulated platforms because it fails in Cirrus-CI.  Lack
 // of QEMU support is suspected.
 #[cfg_attr(qemu, ignore)]
@@ -2498,6 +2935,54 @@ mod linux_errqueue {
         let bytes = msg.bytes;
         assert_eq!(&buf[..bytes], MESSAGE_CONTENTS.as_bytes());
     }
+
+    // Send with MSG_ZEROCOPY over loopback, where the kernel always falls back
+    // to copying, and read the completion notification.
+    #[cfg(target_os = "linux")]
+    #[cfg_attr(qemu, ignore)]
+    #[test]
+    fn test_zerocopy() {
+        use nix::errno::Errno;
+        use nix::poll::{poll, PollFd, PollFlags};
+        use std::net::{TcpListener, TcpStream};
+        use std::os::unix::io::AsRawFd;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let stream =
+            TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (_peer, _) = listener.accept().unwrap();
+        let fd = stream.as_raw_fd();
+
+        if let Err(e) = setsockopt(fd, sockopt::ZeroCopy, &true) {
+            assert_eq!(e, Errno::ENOPROTOOPT);
+            println!("SO_ZEROCOPY not supported, skipping test.");
+            return;
+        }
+        assert!(getsockopt(fd, sockopt::ZeroCopy).unwrap());
+        assert_eq!(recv_errqueue(fd), Err(Errno::EAGAIN));
+
+        let buf = [0x55u8; 4096];
+        for _ in 0..2 {
+            send(fd, &buf, MsgFlags::MSG_ZEROCOPY).unwrap();
+        }
+
+        // Completions for consecutive sends may be coalesced
+        let mut completed = 0;
+        while completed < 2 {
+            let mut fds = [PollFd::new(fd, PollFlags::empty())];
+            assert_eq!(poll(&mut fds, 1000).unwrap(), 1);
+            match recv_errqueue(fd).unwrap() {
+                ErrQueueMsg::ZerocopyCompletion { first, last, copied } => {
+                    assert_eq!(first, completed);
+                    assert!(last >= first);
+                    assert!(copied);
+                    completed = last + 1;
+                }
+                msg => panic!("Unexpected error queue message {:?}", msg),
+            }
+        }
+        assert_eq!(completed, 2);
+    }
 }
 
 // Disable the test on emulated platforms because it fails in Cirrus-CI.  Lack
@@ -184,6 +184,231 @@ fn test_tcp_congestion() {
     assert_eq!(getsockopt(fd, sockopt::TcpCongestion).unwrap(), val);
 }
 
+#[test]
+#[cfg(target_os = "linux")]
+fn test_tcp_info() {
+    use std::net::{TcpListener, TcpStream};
+    use std::os::unix::io::AsRawFd;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let stream = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let _peer = listener.accept().unwrap();
+
+    let info = getsockopt(stream.as_raw_fd(), sockopt::TcpInfoOpt).unwrap();
+    // TCP_ESTABLISHED
+    assert_eq!(info.state(), 1);
+    assert!(info.snd_cwnd() > 0);
+    assert_eq!(info.total_retrans(), 0);
+    assert_eq!(info.as_ref().tcpi_state, info.state());
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_tcp_fastopen() {
+    use nix::unistd::close;
+
+    let fd = socket(
+        AddressFamily::Inet,
+        SockType::Stream,
+        SockFlag::empty(),
+        SockProtocol::Tcp,
+    )
+    .unwrap();
+    setsockopt(fd, sockopt::TcpFastOpen, &5).unwrap();
+    assert_eq!(getsockopt(fd, sockopt::TcpFastOpen).unwrap(), 5);
+
+    assert!(!getsockopt(fd, sockopt::TcpFastOpenConnect).unwrap());
+    setsockopt(fd, sockopt::TcpFastOpenConnect, &true).unwrap();
+    assert!(getsockopt(fd, sockopt::TcpFastOpenConnect).unwrap());
+
+    setsockopt(fd, sockopt::TcpUserTimeout, &10_000).unwrap();
+    assert_eq!(getsockopt(fd, sockopt::TcpUserTimeout).unwrap(), 10_000);
+    close(fd).unwrap();
+}
+
+#[test]
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn test_attach_filter() {
+    use nix::errno::Errno;
+    use nix::sys::socket::{recv, send, socketpair, BpfProgram, MsgFlags};
+
+    let (a, b) = socketpair(
+        AddressFamily::Unix,
+        SockType::Datagram,
+        None,
+        SockFlag::empty(),
+    )
+    .unwrap();
+    let mut buf = [0u8; 8];
+
+    setsockopt(b, sockopt::AttachFilter, &BpfProgram::drop_all()).unwrap();
+    send(a, b"dropped", MsgFlags::empty()).unwrap();
+    assert_eq!(
+        recv(b, &mut buf, MsgFlags::MSG_DONTWAIT),
+        Err(Errno::EAGAIN)
+    );
+
+    setsockopt(b, sockopt::AttachFilter, &BpfProgram::accept_all()).unwrap();
+    send(a, b"kept", MsgFlags::empty()).unwrap();
+    assert_eq!(recv(b, &mut buf, MsgFlags::MSG_DONTWAIT), Ok(4));
+    assert_eq!(&buf[..4], b"kept");
+
+    let udp = socket(
+        AddressFamily::Inet,
+        SockType::Datagram,
+        SockFlag::empty(),
+        None,
+    )
+    .unwrap();
+    setsockopt(udp, sockopt::ReusePort, &true).unwrap();
+    setsockopt(
+        udp,
+        sockopt::AttachReusePortCbpf,
+        &BpfProgram::reuseport_by_cpu(),
+    )
+    .unwrap();
+}
+
+// A sock_fprog can't describe more than c_ushort::MAX instructions
+#[test]
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn test_bpf_program_too_long() {
+    use nix::errno::Errno;
+    use nix::sys::socket::BpfProgram;
+
+    let ret = libc::sock_filter {
+        code: 0x06,
+        jt: 0,
+        jf: 0,
+        k: 0,
+    };
+    let max = usize::from(libc::c_ushort::MAX);
+    assert!(BpfProgram::new(&vec![ret; max]).is_ok());
+    assert_eq!(BpfProgram::new(&vec![ret; max + 1]), Err(Errno::EINVAL));
+}
+
+#[test]
+#[cfg(any(target_os = "freebsd", target_os = "linux"))]
+fn test_reuseport_opts() {
+    let fd = socket(
+        AddressFamily::Inet,
+        SockType::Stream,
+        SockFlag::empty(),
+        SockProtocol::Tcp,
+    )
+    .unwrap();
+    setsockopt(fd, sockopt::ReusePort, &true).unwrap();
+    assert!(getsockopt(fd, sockopt::ReusePort).unwrap());
+
+    #[cfg(target_os = "freebsd")]
+    {
+        setsockopt(fd, sockopt::ReusePortLb, &true).unwrap();
+        assert!(getsockopt(fd, sockopt::ReusePortLb).unwrap());
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        setsockopt(fd, sockopt::IncomingCpu, &0).unwrap();
+        assert_eq!(getsockopt(fd, sockopt::IncomingCpu).unwrap(), 0);
+    }
+}
+
+#[test]
+#[cfg(target_os = "freebsd")]
+fn test_accept_filter() {
+    use crate::skip;
+    use nix::errno::Errno;
+    use nix::sys::socket::{bind, listen, AcceptFilterArg, SockaddrIn};
+    use nix::unistd::close;
+    use std::str::FromStr;
+
+    assert_eq!(
+        AcceptFilterArg::new("a_filter_name_too_long", ""),
+        Err(Errno::ENAMETOOLONG)
+    );
+    let filter = AcceptFilterArg::new("dataready", "").unwrap();
+    assert_eq!(filter.name(), "dataready");
+    assert_eq!(filter.arg(), "");
+
+    let fd = socket(
+        AddressFamily::Inet,
+        SockType::Stream,
+        SockFlag::empty(),
+        SockProtocol::Tcp,
+    )
+    .unwrap();
+    bind(fd, &SockaddrIn::from_str("127.0.0.1:0").unwrap()).unwrap();
+    listen(fd, 1).unwrap();
+    assert_eq!(getsockopt(fd, sockopt::AcceptFilter), Err(Errno::EINVAL));
+    match setsockopt(fd, sockopt::AcceptFilter, &filter) {
+        // accf_data isn't loaded
+        Err(Errno::ENOENT) => {
+            close(fd).unwrap();
+            skip!("test_accept_filter requires accf_data");
+        }
+        r => r.unwrap(),
+    }
+    let got = getsockopt(fd, sockopt::AcceptFilter).unwrap();
+    assert_eq!(got.name(), "dataready");
+    close(fd).unwrap();
+}
+
+#[test]
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn test_raw_icmp_opts() {
+    use nix::sys::socket::Icmp6Filter;
+
+    let mut filter = Icmp6Filter::block_all();
+    filter.pass(129);
+    assert!(filter.will_pass(129));
+    assert!(!filter.will_pass(128));
+    filter.block(129);
+    assert_eq!(filter, Icmp6Filter::block_all());
+    assert!(Icmp6Filter::pass_all().will_pass(255));
+
+    require_capability!("test_raw_icmp_opts", CAP_NET_RAW);
+
+    let fd = socket(
+        AddressFamily::Inet,
+        SockType::Raw,
+        SockFlag::empty(),
+        SockProtocol::Icmp,
+    )
+    .unwrap();
+    setsockopt(fd, sockopt::IpHdrIncl, &true).unwrap();
+    assert!(getsockopt(fd, sockopt::IpHdrIncl).unwrap());
+
+    let fd6 = socket(
+        AddressFamily::Inet6,
+        SockType::Raw,
+        SockFlag::empty(),
+        SockProtocol::IcmpV6,
+    )
+    .unwrap();
+    assert_eq!(getsockopt(fd6, sockopt::Ipv6Checksum).unwrap(), 2);
+    let mut filter = Icmp6Filter::block_all();
+    filter.pass(129);
+    setsockopt(fd6, sockopt::Icmp6Filter, &filter).unwrap();
+    assert_eq!(getsockopt(fd6, sockopt::Icmp6Filter).unwrap(), filter);
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_busy_poll() {
+    let fd = socket(
+        AddressFamily::Inet,
+        SockType::Datagram,
+        SockFlag::empty(),
+        SockProtocol::Udp,
+    )
+    .unwrap();
+    setsockopt(fd, sockopt::BusyPoll, &0).unwrap();
+    assert_eq!(getsockopt(fd, sockopt::BusyPoll).unwrap(), 0);
+    setsockopt(fd, sockopt::PreferBusyPoll, &true).unwrap();
+    assert!(getsockopt(fd, sockopt::PreferBusyPoll).unwrap());
+    setsockopt(fd, sockopt::BusyPollBudget, &0).unwrap();
+}
+
 #[test]
 #[cfg(any(target_os = "android", target_os = "linux"))]
 fn test_bindtodevice() {
@@ -203,6 +428,32 @@ fn test_bindtodevice() {
     assert_eq!(getsockopt(fd, sockopt::BindToDevice).unwrap(), val);
 }
 
+#[test]
+#[cfg(any(target_os = "ios", target_os = "macos"))]
+fn test_bound_if() {
+    let lo = nix::net::if_::if_nametoindex("lo0").unwrap();
+
+    let fd4 = socket(
+        AddressFamily::Inet,
+        SockType::Datagram,
+        SockFlag::empty(),
+        None,
+    )
+    .unwrap();
+    setsockopt(fd4, sockopt::IpBoundIf, &lo).unwrap();
+    assert_eq!(getsockopt(fd4, sockopt::IpBoundIf).unwrap(), lo);
+
+    let fd6 = socket(
+        AddressFamily::Inet6,
+        SockType::Datagram,
+        SockFlag::empty(),
+        None,
+    )
+    .unwrap();
+    setsockopt(fd6, sockopt::Ipv6BoundIf, &lo).unwrap();
+    assert_eq!(getsockopt(fd6, sockopt::Ipv6BoundIf).unwrap(), lo);
+}
+
 #[test]
 fn test_so_tcp_keepalive() {
     let fd = socket(
@@ -259,6 +510,95 @@ fn test_ttl_opts() {
         .expect("setting ipv6ttl on an inet6 socket should succeed");
 }
 
+#[test]
+fn test_raw_sockopt() {
+    use nix::sys::socket::{getsockopt_raw, setsockopt_raw};
+    use nix::unistd::close;
+
+    let fd = socket(
+        AddressFamily::Inet,
+        SockType::Stream,
+        SockFlag::empty(),
+        None,
+    )
+    .unwrap();
+    let one: libc::c_int = 1;
+    setsockopt_raw(
+        fd,
+        libc::IPPROTO_TCP,
+        libc::TCP_NODELAY,
+        &one.to_ne_bytes(),
+    )
+    .unwrap();
+    assert!(getsockopt(fd, sockopt::TcpNoDelay).unwrap());
+
+    let mut buf = [0u8; 4];
+    let len = getsockopt_raw(fd, libc::IPPROTO_TCP, libc::TCP_NODELAY, &mut buf)
+        .unwrap();
+    assert_eq!(len, buf.len());
+    assert_ne!(libc::c_int::from_ne_bytes(buf), 0);
+    close(fd).unwrap();
+}
+
+#[test]
+fn test_sockopt_raw_macro() {
+    use nix::unistd::close;
+
+    nix::sockopt_raw!(
+        /// The receive buffer size, defined outside of Nix
+        RawRcvBuf, Both, libc::SOL_SOCKET, libc::SO_RCVBUF, libc::c_int);
+    nix::sockopt_raw!(SoType, GetOnly, libc::SOL_SOCKET, libc::SO_TYPE, i32);
+
+    let fd = socket(
+        AddressFamily::Inet,
+        SockType::Stream,
+        SockFlag::empty(),
+        None,
+    )
+    .unwrap();
+    assert_eq!(getsockopt(fd, SoType).unwrap(), libc::SOCK_STREAM);
+
+    // The kernel may round the size up, but never down
+    setsockopt(fd, RawRcvBuf, &4096).unwrap();
+    let rcvbuf = getsockopt(fd, RawRcvBuf).unwrap();
+    assert!(rcvbuf >= 4096);
+    assert_eq!(rcvbuf as usize, getsockopt(fd, sockopt::RcvBuf).unwrap());
+    close(fd).unwrap();
+}
+
+#[test]
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn test_multicast_opts() {
+    use nix::net::if_::if_nametoindex;
+    use nix::sys::socket::{IpMembershipRequest, IpSourceMembershipRequest};
+    use nix::unistd::close;
+    use std::net::Ipv4Addr;
+
+    let fd = socket(
+        AddressFamily::Inet,
+        SockType::Datagram,
+        SockFlag::empty(),
+        None,
+    )
+    .unwrap();
+    let group = Ipv4Addr::new(239, 1, 2, 3);
+    let lo = if_nametoindex("lo").unwrap();
+
+    let mreq = IpMembershipRequest::with_ifindex(group, lo);
+    setsockopt(fd, sockopt::IpAddMembership, &mreq).unwrap();
+    setsockopt(fd, sockopt::IpDropMembership, &mreq).unwrap();
+
+    let lo_addr = Ipv4Addr::LOCALHOST;
+    let source = Ipv4Addr::new(127, 0, 0, 2);
+    let smreq = IpSourceMembershipRequest::new(group, source, Some(lo_addr));
+    setsockopt(fd, sockopt::IpAddSourceMembership, &smreq).unwrap();
+    setsockopt(fd, sockopt::IpDropSourceMembership, &smreq).unwrap();
+
+    setsockopt(fd, sockopt::IpMulticastIf, &lo_addr).unwrap();
+    assert_eq!(getsockopt(fd, sockopt::IpMulticastIf).unwrap(), lo_addr);
+    close(fd).unwrap();
+}
+
 #[test]
 #[cfg(any(target_os = "ios", target_os = "macos"))]
 fn test_dontfrag_opts() {
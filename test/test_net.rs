@@ -17,3 +17,186 @@ const LOOPBACK: &[u8] = b"loop";
 fn test_if_nametoindex() {
     if_nametoindex(LOOPBACK).expect("assertion failed");
 }
+
+#[test]
+fn test_if_indextoname() {
+    let index = if_nametoindex(LOOPBACK).unwrap();
+    let name = if_indextoname(index).unwrap();
+    assert_eq!(name.as_bytes(), LOOPBACK);
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+mod ifreq {
+    use super::LOOPBACK;
+    use crate::*;
+    use nix::errno::Errno;
+    use nix::net::if_::InterfaceFlags;
+    use nix::net::ifreq::*;
+    use nix::sys::socket::{
+        socket, AddressFamily, SockFlag, SockType, SockaddrIn,
+    };
+    use nix::unistd::close;
+
+    fn sock() -> std::os::unix::io::RawFd {
+        socket(
+            AddressFamily::Inet,
+            SockType::Datagram,
+            SockFlag::SOCK_CLOEXEC,
+            None,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_get() {
+        let fd = sock();
+
+        assert!(get_mtu(fd, LOOPBACK).unwrap() > 0);
+        let flags = get_flags(fd, LOOPBACK).unwrap();
+        assert!(flags.contains(InterfaceFlags::IFF_LOOPBACK));
+        let (hwtype, hwaddr) = get_hwaddr(fd, LOOPBACK).unwrap();
+        assert_eq!(hwtype, libc::ARPHRD_LOOPBACK);
+        assert_eq!(hwaddr, [0; 6]);
+        if flags.contains(InterfaceFlags::IFF_UP) {
+            assert_eq!(
+                get_addr(fd, LOOPBACK).unwrap(),
+                SockaddrIn::new(127, 0, 0, 1, 0)
+            );
+        }
+
+        assert_eq!(get_mtu(fd, "nonexistent0"), Err(Errno::ENODEV));
+        assert_eq!(
+            get_mtu(fd, "an_interface_name_too_long"),
+            Err(Errno::ENAMETOOLONG)
+        );
+        close(fd).unwrap();
+    }
+
+    #[test]
+    fn test_set_mtu() {
+        require_capability!("test_set_mtu", CAP_NET_ADMIN);
+        let fd = sock();
+
+        let mtu = get_mtu(fd, LOOPBACK).unwrap();
+        set_mtu(fd, LOOPBACK, mtu).unwrap();
+        assert_eq!(get_mtu(fd, LOOPBACK).unwrap(), mtu);
+        close(fd).unwrap();
+    }
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+mod tun {
+    use crate::*;
+    use nix::errno::Errno;
+    use nix::net::if_::if_nametoindex;
+    use nix::net::tun::*;
+    use nix::unistd::close;
+
+    #[test]
+    fn test_tun_open() {
+        require_capability!("test_tun_open", CAP_NET_ADMIN);
+        if !std::path::Path::new("/dev/net/tun").exists() {
+            skip!("/dev/net/tun is not available. Skipping test.");
+        }
+
+        let flags = TunFlags::IFF_TUN | TunFlags::IFF_NO_PI;
+        let (fd, name) = open("nixtest%d", flags).unwrap();
+        assert!(name.starts_with("nixtest"));
+        if_nametoindex(name.as_str()).unwrap();
+
+        // A second, exclusive open of the same device must fail
+        assert_eq!(
+            open(name.as_str(), flags | TunFlags::IFF_TUN_EXCL),
+            Err(Errno::EBUSY)
+        );
+        set_persist(fd, false).unwrap();
+
+        close(fd).unwrap();
+        assert_eq!(if_nametoindex(name.as_str()), Err(Errno::ENODEV));
+    }
+}
+
+#[cfg(any(
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "macos",
+    target_os = "openbsd"
+))]
+mod route {
+    use nix::errno::Errno;
+    use nix::net::route::*;
+    use nix::sys::socket::{socket, AddressFamily, SockFlag, SockType};
+    use nix::unistd::close;
+
+    #[test]
+    fn test_route_messages() {
+        let mut buf = [0u8; 10];
+        buf[..2].copy_from_slice(&6u16.to_ne_bytes());
+        buf[2] = libc::RTM_VERSION as u8;
+        buf[3] = libc::RTM_IFINFO as u8;
+        buf[6..8].copy_from_slice(&4u16.to_ne_bytes());
+        buf[9] = 0xff;
+        let mut msgs = RouteMessages::new(&buf);
+        let msg = msgs.next().unwrap().unwrap();
+        assert_eq!(msg.as_bytes().len(), 6);
+        assert_eq!(msg.version(), libc::RTM_VERSION as u8);
+        assert_eq!(msg.message_type(), Some(RouteMessageType::RTM_IFINFO));
+        // Too short to hold an if_msghdr
+        assert!(msg.if_msghdr().is_none());
+        let msg = msgs.next().unwrap().unwrap();
+        assert_eq!(msg.message_type(), None);
+        assert!(msgs.next().is_none());
+
+        let mut msgs = RouteMessages::new(&buf[..5]);
+        assert_eq!(msgs.next(), Some(Err(Errno::EBADMSG)));
+        assert!(msgs.next().is_none());
+    }
+
+    #[test]
+    fn test_route_socket() {
+        let fd = socket(
+            AddressFamily::Route,
+            SockType::Raw,
+            SockFlag::empty(),
+            None,
+        )
+        .unwrap();
+        close(fd).unwrap();
+    }
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+mod uevent {
+    use nix::errno::Errno;
+    use nix::net::uevent::*;
+    use nix::sys::socket::SockFlag;
+
+    #[test]
+    fn test_parse() {
+        let msg = b"add@/devices/virtual/net/tun0\0ACTION=add\0\
+                    DEVPATH=/devices/virtual/net/tun0\0SUBSYSTEM=net\0\
+                    INTERFACE=tun0\0IFINDEX=5\0SEQNUM=1234\0";
+        let event = UEvent::parse(msg).unwrap();
+        assert_eq!(event.action().unwrap(), "add");
+        assert_eq!(event.devpath().unwrap(), "/devices/virtual/net/tun0");
+        assert_eq!(event.subsystem().unwrap(), "net");
+        assert_eq!(event.get("IFINDEX").unwrap(), "5");
+        assert!(event.get("DEVNAME").is_none());
+        assert_eq!(event.iter().count(), 6);
+
+        // udev's messages start with their own binary header
+        assert_eq!(UEvent::parse(b"libudev\0\xfe\xed"), Err(Errno::EBADMSG));
+    }
+
+    #[test]
+    fn test_socket() {
+        let mut sock =
+            UEventSocket::new(SockFlag::SOCK_CLOEXEC | SockFlag::SOCK_NONBLOCK)
+                .unwrap();
+        // Events may arrive at any time, but none should be malformed
+        match sock.next().unwrap() {
+            Ok(_) | Err(Errno::EAGAIN) => (),
+            Err(e) => panic!("unexpected error {}", e),
+        }
+    }
+}